@@ -0,0 +1,25 @@
+//! Compares searching with and without an arena. Run with `cargo bench --features arena`.
+
+use std::{hint::black_box, time::Instant};
+
+use reversi_solver::{Game, arena::Arena, solve::{solve_at_depth, solve_at_depth_arena}};
+
+fn main() {
+    let mut rng = fastrand::Rng::with_seed(1);
+    let game = Game::random_position_at_move_n(20, &mut rng).unwrap();
+    let depth = 4;
+
+    let start = Instant::now();
+    black_box(solve_at_depth(black_box(&game), depth));
+    println!("{:<30} {:>10.1?}", "solve_at_depth", start.elapsed());
+
+    let mut arena = Arena::new();
+    let start = Instant::now();
+    black_box(solve_at_depth_arena(black_box(&game), depth, &mut arena));
+    println!("{:<30} {:>10.1?}", "solve_at_depth_arena", start.elapsed());
+
+    let mut nodes = 0;
+    game.depth_first_search(depth + 1, |_, _| nodes += 1);
+
+    println!("positions created: {} without an arena, {} with one", nodes - 1, arena.created());
+}
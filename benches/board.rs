@@ -0,0 +1,55 @@
+//! Simple timing benchmarks. These avoid a benchmarking framework, so run them with
+//! `cargo bench` and compare the printed timings.
+
+use std::{hint::black_box, time::Instant};
+
+use reversi_solver::{Game, board::{Board, Cell, Player}};
+
+fn bench(name: &str, iterations: u32, mut f: impl FnMut()) {
+    let start = Instant::now();
+
+    for _ in 0..iterations {
+        f();
+    }
+
+    println!("{name:<40} {:>10.1?} / iter", start.elapsed() / iterations);
+}
+
+fn midgame_board() -> Board {
+    let mut rng = fastrand::Rng::with_seed(1);
+    let game = Game::random_position_at_move_n(30, &mut rng).unwrap();
+
+    let mut board = Board::new();
+    for (idx, cell) in game.iter().enumerate() {
+        board.set_cell_idx(idx, cell);
+    }
+
+    board
+}
+
+fn main() {
+    let board = midgame_board();
+
+    bench("iter_empty (sum)", 1_000_000, || {
+        black_box(black_box(&board).iter_empty().sum::<usize>());
+    });
+
+    bench("Vec of empty cells (sum)", 1_000_000, || {
+        let empty = black_box(&board).iter().enumerate()
+            .filter(|(_, cell)| **cell == Cell::Empty)
+            .map(|(idx, _)| idx)
+            .collect::<Vec<_>>();
+
+        black_box(empty.iter().sum::<usize>());
+    });
+
+    bench("iter_occupied (first)", 1_000_000, || {
+        black_box(black_box(&board).iter_occupied(Player::One).next());
+    });
+
+    bench("Vec of occupied cells (first)", 1_000_000, || {
+        let occupied = black_box(&board).iter_occupied(Player::One).collect::<Vec<_>>();
+
+        black_box(occupied.first().copied());
+    });
+}
@@ -0,0 +1,84 @@
+//! Compares how many positions [`negamax`] visits with and without alpha-beta pruning, and
+//! with and without move ordering. Run with `cargo bench --bench negamax`.
+
+use std::time::Instant;
+
+use reversi_solver::{Game, board::SIZE, solve::{negamax, order_moves}};
+
+/// Mirrors [`negamax`] (without its transposition table), counting every position visited,
+/// with pruning and move ordering turned on or off.
+fn count_nodes(game: &Game, mut alpha: isize, beta: isize, prune: bool, order: bool, nodes: &mut u64) -> isize {
+    *nodes += 1;
+
+    let moves = game.move_indices();
+
+    if moves.is_empty() {
+        return 0;
+    }
+
+    if moves.iter().any(|possible_move| game.is_winning_move_idx(*possible_move, game.current_player()).unwrap()) {
+        return (SIZE as isize + 1 - game.total_moves() as isize) / 2;
+    }
+
+    let moves = if order { order_moves(game, &moves) } else { moves };
+    let mut best_score = -(SIZE as isize);
+
+    for possible_move in moves {
+        let mut new_game = game.clone();
+        new_game.play_idx(possible_move).unwrap();
+
+        let score = -count_nodes(&new_game, -beta, -alpha, prune, order, nodes);
+
+        best_score = best_score.max(score);
+        alpha = alpha.max(best_score);
+
+        if prune && alpha >= beta {
+            break;
+        }
+    }
+
+    best_score
+}
+
+fn main() {
+    let mut rng = fastrand::Rng::with_seed(5);
+    let game = Game::random_position_at_move_n(SIZE - 4 - 9, &mut rng).unwrap();
+    let window = (-(SIZE as isize), SIZE as isize);
+
+    let mut unpruned = 0;
+    let unpruned_score = count_nodes(&game, window.0, window.1, false, false, &mut unpruned);
+
+    let mut pruned = 0;
+    let pruned_score = count_nodes(&game, window.0, window.1, true, false, &mut pruned);
+
+    assert_eq!(unpruned_score, pruned_score);
+
+    let start = Instant::now();
+    let score = negamax(&game, window.0, window.1).unwrap();
+    let elapsed = start.elapsed();
+
+    assert_eq!(score, pruned_score);
+
+    println!("{:<40} {:>12} nodes", "negamax without pruning", unpruned);
+    println!("{:<40} {:>12} nodes", "negamax with alpha-beta", pruned);
+    println!("{:<40} {:>11.1}x", "reduction", unpruned as f64 / pruned as f64);
+    println!("{:<40} {:>10.1?}", "negamax with alpha-beta (time)", elapsed);
+
+    let (mut unordered, mut ordered) = (0, 0);
+
+    for seed in 0..5 {
+        let mut rng = fastrand::Rng::with_seed(seed);
+        let Ok(game) = Game::random_position_at_move_n(SIZE - 4 - 11, &mut rng) else {
+            continue;
+        };
+
+        let unordered_score = count_nodes(&game, window.0, window.1, true, false, &mut unordered);
+        let ordered_score = count_nodes(&game, window.0, window.1, true, true, &mut ordered);
+
+        assert_eq!(unordered_score, ordered_score);
+    }
+
+    println!("{:<40} {:>12} nodes", "alpha-beta without move ordering", unordered);
+    println!("{:<40} {:>12} nodes", "alpha-beta with move ordering", ordered);
+    println!("{:<40} {:>11.1}x", "reduction", unordered as f64 / ordered as f64);
+}
@@ -0,0 +1,57 @@
+/// A pool of reusable values, handed out with [`Arena::get`] and returned with
+/// [`Arena::release`].
+///
+/// Values are reused with [`Clone::clone_from`], which lets types that own buffers keep them
+/// rather than allocating again. [`Arena::created`] counts the values that could not be served
+/// from the pool.
+#[derive(Clone, Debug)]
+pub struct Arena<T> {
+    pool: Vec<T>,
+    created: usize,
+}
+
+impl<T: Clone> Arena<T> {
+    pub fn new() -> Self {
+        Arena { pool: Vec::new(), created: 0 }
+    }
+
+    /// Pre-allocates `capacity` copies of `template`.
+    pub fn with_capacity(capacity: usize, template: &T) -> Self {
+        Arena { pool: vec![template.clone(); capacity], created: capacity }
+    }
+
+    /// Returns a copy of `value`, reusing a pooled instance when one is available.
+    pub fn get(&mut self, value: &T) -> T {
+        match self.pool.pop() {
+            Some(mut pooled) => {
+                pooled.clone_from(value);
+                pooled
+            }
+            None => {
+                self.created += 1;
+                value.clone()
+            }
+        }
+    }
+
+    /// Returns a value to the pool for later reuse.
+    pub fn release(&mut self, value: T) {
+        self.pool.push(value);
+    }
+
+    /// How many values this arena has created, including pre-allocated ones.
+    pub fn created(&self) -> usize {
+        self.created
+    }
+
+    /// How many values are currently waiting in the pool.
+    pub fn available(&self) -> usize {
+        self.pool.len()
+    }
+}
+
+impl<T: Clone> Default for Arena<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,6 +1,10 @@
-use std::fmt;
+use std::{collections::VecDeque, fmt};
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+use anyhow::{Result, anyhow};
+
+use crate::zobrist::{cell_state, ZOBRIST_TABLE};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Player {
     One,
     Two,
@@ -13,9 +17,26 @@ impl Player {
             Player::Two => Player::One
         }
     }
+
+    pub fn to_char(&self) -> char {
+        Cell::Player(*self).to_char()
+    }
+}
+
+impl TryFrom<char> for Player {
+    type Error = anyhow::Error;
+
+    /// Parses `X` or `O`, in either case.
+    fn try_from(character: char) -> Result<Player> {
+        match character {
+            'X' | 'x' => Ok(Player::One),
+            'O' | 'o' => Ok(Player::Two),
+            _ => Err(anyhow!("Invalid player: {}, expected X or O", character)),
+        }
+    }
 }
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
 pub enum Cell {
     Empty,
     Player(Player),
@@ -25,6 +46,26 @@ pub const WIDTH: usize = 8;
 pub const HEIGHT: usize = 8;
 pub const SIZE: usize = WIDTH * HEIGHT;
 
+/// Bitboard of the four corners: cells 0, 7, 56 and 63 (bit `i` is cell `i`, counted row by
+/// row from the top left).
+pub const CORNER_MASK: u64 = 0x8100_0000_0000_0081;
+
+/// Bitboard of the outer ring of 28 cells, corners included, matching [`is_edge`]: the top
+/// and bottom rows (bits 0-7 and 56-63) and the left and right columns (bits `8 * y` and
+/// `8 * y + 7`).
+pub const EDGE_MASK: u64 = 0xFF81_8181_8181_81FF;
+
+/// Bitboard of the four X-squares, diagonally inside each corner: cells 9, 14, 49 and 54.
+pub const X_SQUARE_MASK: u64 = 0x0042_0000_0000_4200;
+
+/// Bitboard of the eight C-squares, on an edge next to each corner: cells 1, 6, 8, 15, 48, 55,
+/// 57 and 62.
+pub const C_SQUARE_MASK: u64 = 0x4281_0000_0000_8142;
+
+/// Bitboard of the inner 6x6 square, everything off the edge: columns 1-6 of rows 1-6. This
+/// is the complement of [`EDGE_MASK`].
+pub const INNER_MASK: u64 = 0x007E_7E7E_7E7E_7E00;
+
 impl Cell {
     pub fn to_char(&self) -> char {
         match self {
@@ -35,49 +76,916 @@ impl Cell {
     }
 }
 
-/// Represents the internal state of the game board.
-#[derive(Clone, Debug, PartialEq)]
+impl TryFrom<char> for Cell {
+    type Error = anyhow::Error;
+
+    /// Parses `X` and `O` as discs, and `-`, `.` or `*` (an empty square marked as a legal
+    /// move) as empty.
+    fn try_from(character: char) -> Result<Cell> {
+        match character {
+            'X' => Ok(Cell::Player(Player::One)),
+            'O' => Ok(Cell::Player(Player::Two)),
+            '-' | '.' | '*' => Ok(Cell::Empty),
+            _ => Err(anyhow!("Invalid character: {}", character)),
+        }
+    }
+}
+
+/// A move, as the cell the disc is placed on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Move {
+    index: usize,
+}
+
+impl Move {
+    pub fn new(x: usize, y: usize) -> Move {
+        Move::from_index(at_pos(x, y))
+    }
+
+    /// The move placing a disc on cell `index`, counted row by row from the top left.
+    pub fn from_index(index: usize) -> Move {
+        debug_assert!(index < SIZE, "Move out of bounds: {}", index);
+
+        Move { index }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn x(&self) -> usize {
+        self.index % WIDTH
+    }
+
+    pub fn y(&self) -> usize {
+        self.index / WIDTH
+    }
+
+    /// The move in algebraic notation: a column letter (`a`-`h`) followed by a row digit
+    /// (`1`-`8`), e.g. `d3`.
+    pub fn to_coord(&self) -> String {
+        let column = (b'a' + self.x() as u8) as char;
+        let row = (b'1' + self.y() as u8) as char;
+
+        format!("{column}{row}")
+    }
+
+    /// Parses a move written in algebraic notation (see [`Move::to_coord`]). The column letter
+    /// may be upper or lower case, so `D3` and `d3` are the same move.
+    pub fn from_coord(s: &str) -> Result<Move> {
+        let mut characters = s.chars();
+
+        let (Some(column), Some(row), None) = (characters.next(), characters.next(), characters.next()) else {
+            Err(anyhow!("Invalid move: {:?}, expected a column letter and a row digit like d3", s))?
+        };
+
+        let column = column.to_ascii_lowercase();
+
+        if !('a'..='h').contains(&column) {
+            Err(anyhow!("Invalid move: {:?}, the column must be a letter from a to h", s))?;
+        }
+
+        if !('1'..='8').contains(&row) {
+            Err(anyhow!("Invalid move: {:?}, the row must be a digit from 1 to 8", s))?;
+        }
+
+        Ok(Move::new(column as usize - 'a' as usize, row as usize - '1' as usize))
+    }
+}
+
+/// One of the 8 symmetries of the square board.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Symmetry {
+    Identity,
+    /// Rotation by 90 degrees clockwise.
+    Rot90,
+    Rot180,
+    /// Rotation by 270 degrees clockwise.
+    Rot270,
+    /// Mirror left to right.
+    FlipH,
+    /// Mirror top to bottom.
+    FlipV,
+    /// Mirror along the main (top-left to bottom-right) diagonal.
+    FlipDiag,
+    /// Mirror along the anti (top-right to bottom-left) diagonal.
+    FlipAntiDiag,
+}
+
+impl Symmetry {
+    pub const ALL: [Symmetry; 8] = [
+        Symmetry::Identity,
+        Symmetry::Rot90,
+        Symmetry::Rot180,
+        Symmetry::Rot270,
+        Symmetry::FlipH,
+        Symmetry::FlipV,
+        Symmetry::FlipDiag,
+        Symmetry::FlipAntiDiag,
+    ];
+
+    /// The symmetry that undoes this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rot90 => Symmetry::Rot270,
+            Symmetry::Rot270 => Symmetry::Rot90,
+            symmetry => symmetry,
+        }
+    }
+
+    /// Maps a position to where it ends up after applying this symmetry.
+    pub fn apply_to_pos(self, x: usize, y: usize) -> (usize, usize) {
+        let (max_x, max_y) = (WIDTH - 1, HEIGHT - 1);
+
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rot90 => (max_y - y, x),
+            Symmetry::Rot180 => (max_x - x, max_y - y),
+            Symmetry::Rot270 => (y, max_x - x),
+            Symmetry::FlipH => (max_x - x, y),
+            Symmetry::FlipV => (x, max_y - y),
+            Symmetry::FlipDiag => (y, x),
+            Symmetry::FlipAntiDiag => (max_y - y, max_x - x),
+        }
+    }
+
+    /// Maps a cell index to where it ends up after applying this symmetry.
+    pub fn apply_to_index(self, idx: usize) -> usize {
+        let (x, y) = self.apply_to_pos(idx % WIDTH, idx / WIDTH);
+
+        at_pos(x, y)
+    }
+}
+
+/// The strategic role of a square.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum CellType {
+    Corner,
+    /// On an edge, but not a corner or C-square.
+    Edge,
+    /// Diagonally next to a corner.
+    XSquare,
+    /// On an edge, next to a corner.
+    CSquare,
+    /// Not on an edge.
+    Interior,
+}
+
+impl CellType {
+    pub const ALL: [CellType; 5] = [CellType::Corner, CellType::Edge, CellType::XSquare, CellType::CSquare, CellType::Interior];
+
+    pub fn of(idx: usize) -> CellType {
+        if is_corner(idx) {
+            CellType::Corner
+        } else if is_x_square(idx) {
+            CellType::XSquare
+        } else if is_c_square(idx) {
+            CellType::CSquare
+        } else if is_edge(idx) {
+            CellType::Edge
+        } else {
+            CellType::Interior
+        }
+    }
+}
+
+/// Distance from a cell to the nearest corner along each axis.
+fn corner_distance(idx: usize) -> (usize, usize) {
+    let (x, y) = (idx % WIDTH, idx / WIDTH);
+
+    (x.min(WIDTH - 1 - x), y.min(HEIGHT - 1 - y))
+}
+
+pub fn is_corner(idx: usize) -> bool {
+    corner_distance(idx) == (0, 0)
+}
+
+/// Whether the cell is on the outer ring of the board, corners included.
+pub fn is_edge(idx: usize) -> bool {
+    let (x, y) = corner_distance(idx);
+
+    x == 0 || y == 0
+}
+
+pub fn is_x_square(idx: usize) -> bool {
+    corner_distance(idx) == (1, 1)
+}
+
+pub fn is_c_square(idx: usize) -> bool {
+    matches!(corner_distance(idx), (0, 1) | (1, 0))
+}
+
+/// Maps a cell (or move) index to where `sym` moves it, matching [`Board::apply_symmetry`].
+pub fn apply_symmetry_to_index(idx: usize, sym: Symmetry) -> usize {
+    sym.apply_to_index(idx)
+}
+
+/// Every cell state, for handing out references to cells that are only stored as bits.
+const CELLS: [Cell; 3] = [Cell::Empty, Cell::Player(Player::One), Cell::Player(Player::Two)];
+
+/// Represents the internal state of the game board as one bitboard per player, with bit `i`
+/// set when cell `i` holds that player's disc. The two never overlap.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
 pub struct Board {
-    cells: [Cell; SIZE],
+    player_one: u64,
+    player_two: u64,
 }
 
 pub fn at_pos(x: usize, y: usize) -> usize {
     x + y * WIDTH
 }
 
+/// The cells in the leftmost column (`x == 0`).
+pub(crate) const LEFT_COLUMN: u64 = 0x0101_0101_0101_0101;
+
+/// Every cell except those in the leftmost column (`x == 0`).
+const NOT_LEFT_COLUMN: u64 = !LEFT_COLUMN;
+
+/// Every cell except those in the rightmost column (`x == 7`).
+const NOT_RIGHT_COLUMN: u64 = 0x7F7F_7F7F_7F7F_7F7F;
+
+/// The 8 directions as bit shifts (positive shifts left), each with the cells a one cell step
+/// in that direction can land on without wrapping around to the other side of the board.
+const SHIFTS: [(i32, u64); 8] = [
+    (1, NOT_LEFT_COLUMN),
+    (-1, NOT_RIGHT_COLUMN),
+    (WIDTH as i32, u64::MAX),
+    (-(WIDTH as i32), u64::MAX),
+    (WIDTH as i32 + 1, NOT_LEFT_COLUMN),
+    (-(WIDTH as i32 + 1), NOT_RIGHT_COLUMN),
+    (WIDTH as i32 - 1, NOT_RIGHT_COLUMN),
+    (-(WIDTH as i32 - 1), NOT_LEFT_COLUMN),
+];
+
+fn shift(bits: u64, amount: i32) -> u64 {
+    if amount > 0 {
+        bits << amount
+    } else {
+        bits >> -amount
+    }
+}
+
+/// The legal moves for `player` as a bitboard (bit `i` is cell `i`), given both players'
+/// discs and the empty cells.
+///
+/// For each direction, a Kogge-Stone fill floods out from `player`'s discs through runs of
+/// `opponent`'s, in three doubling steps instead of one step per cell; one more step from the
+/// end of each run lands on the empty cells that would capture it.
+pub fn get_moves_bitboard(player: u64, opponent: u64, empty: u64) -> u64 {
+    SHIFTS.iter().fold(0, |moves, (amount, mask)| {
+        let mut flood = player;
+        let mut propagator = opponent & mask;
+
+        flood |= propagator & shift(flood, *amount);
+        propagator &= shift(propagator, *amount);
+        flood |= propagator & shift(flood, 2 * amount);
+        propagator &= shift(propagator, 2 * amount);
+        flood |= propagator & shift(flood, 4 * amount);
+
+        moves | shift(flood & opponent, *amount) & mask & empty
+    })
+}
+
+/// The `opponent` discs that `player` placing a disc at `mv` would flip, as a bitboard.
+pub(crate) fn flips_bitboard(mv: usize, player: u64, opponent: u64) -> u64 {
+    SHIFTS.iter().fold(0, |flips, (amount, mask)| {
+        let mut run = 0;
+        let mut next = shift(1 << mv, *amount) & mask;
+
+        while next & opponent != 0 {
+            run |= next;
+            next = shift(next, *amount) & mask;
+        }
+
+        if next & player != 0 { flips | run } else { flips }
+    })
+}
+
+/// One direction along each of the four lines through a cell: its row, its column, and its
+/// diagonals going down to the right and down to the left.
+const LINE_DIRECTIONS: [(isize, isize); 4] = [(1, 0), (0, 1), (1, 1), (-1, 1)];
+
+/// The indices of row `y`, from left to right.
+fn row(y: usize) -> impl Iterator<Item = usize> {
+    (0..WIDTH).map(move |x| at_pos(x, y))
+}
+
+/// The indices of column `x`, from top to bottom.
+fn col(x: usize) -> impl Iterator<Item = usize> {
+    (0..HEIGHT).map(move |y| at_pos(x, y))
+}
+
+/// The indices of the diagonal from `(x, y)` down to the edge of the board, stepping `x_dir`
+/// (`1` or `-1`) columns per row.
+fn diagonal(x: usize, y: usize, x_dir: isize) -> impl Iterator<Item = usize> {
+    (0..).map(move |step| (x.wrapping_add_signed(x_dir * step as isize), y + step))
+        .take_while(|(x, y)| *x < WIDTH && *y < HEIGHT)
+        .map(|(x, y)| at_pos(x, y))
+}
+
+/// Iterates over the indices of the set bits of `mask`, lowest first.
+pub(crate) fn iter_bits(mut mask: u64) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if mask == 0 {
+            return None;
+        }
+
+        let idx = mask.trailing_zeros() as usize;
+        mask &= mask - 1;
+
+        Some(idx)
+    })
+}
+
 impl Board {
     pub fn new() -> Board {
         Board {
-            cells: [Cell::Empty; SIZE],
+            player_one: 0,
+            player_two: 0,
+        }
+    }
+
+    /// Both players' bitboards, as `(player_one, player_two)`.
+    pub fn to_bitboards(&self) -> (u64, u64) {
+        (self.player_one, self.player_two)
+    }
+
+    /// Builds a board from each player's bitboard (bit `i` is cell `i`). Where they overlap,
+    /// player one's disc wins; use [`Board::from_hex`] to reject overlapping masks instead.
+    pub fn from_bitboards(p1: u64, p2: u64) -> Board {
+        Board {
+            player_one: p1,
+            player_two: p2 & !p1,
         }
     }
 
     pub fn total_moves(&self) -> usize {
-        self.cells.iter().filter(|cell| **cell != Cell::Empty).count()
+        (self.player_one | self.player_two).count_ones() as usize
+    }
+
+    /// The inner 6x6 square as a bitboard, see [`INNER_MASK`].
+    pub fn inner_squares_mask() -> u64 {
+        INNER_MASK
+    }
+
+    /// The occupied cells of row `y`, with bit `x` set if the cell at `(x, y)` holds a disc.
+    pub fn row_fill_mask(&self, y: usize) -> u8 {
+        (0..WIDTH).filter(|x| self.get_cell(*x, y) != Cell::Empty).fold(0, |mask, x| mask | 1 << x)
+    }
+
+    /// The occupied cells of column `x`, with bit `y` set if the cell at `(x, y)` holds a disc.
+    pub fn col_fill_mask(&self, x: usize) -> u8 {
+        (0..HEIGHT).filter(|y| self.get_cell(x, *y) != Cell::Empty).fold(0, |mask, y| mask | 1 << y)
+    }
+
+    /// Whether every cell is occupied.
+    pub fn is_full(&self) -> bool {
+        self.total_moves() == SIZE
+    }
+
+    /// Whether every cell is empty.
+    pub fn is_empty_board(&self) -> bool {
+        self.total_moves() == 0
+    }
+
+    /// The Zobrist hash of the board, computed from scratch: the XOR of the
+    /// [`ZOBRIST_TABLE`](crate::zobrist::ZOBRIST_TABLE) key of every cell.
+    pub fn compute_hash(&self) -> u64 {
+        self.iter().enumerate().fold(0, |hash, (idx, cell)| hash ^ ZOBRIST_TABLE[idx][cell_state(*cell)])
     }
 
     pub fn get_cell(&self, x: usize, y: usize) -> Cell {
-        self.cells[at_pos(x, y)]
+        self.get_cell_idx(at_pos(x, y))
     }
 
     pub fn set_cell(&mut self, x: usize, y: usize, cell: Cell) {
-        self.cells[at_pos(x, y)] = cell;
+        self.set_cell_idx(at_pos(x, y), cell);
     }
 
     pub fn set_cell_idx(&mut self, idx: usize, cell: Cell) {
-        self.cells[idx] = cell;
+        let bit = 1 << idx;
+
+        self.player_one &= !bit;
+        self.player_two &= !bit;
+
+        match cell {
+            Cell::Empty => {}
+            Cell::Player(Player::One) => self.player_one |= bit,
+            Cell::Player(Player::Two) => self.player_two |= bit,
+        }
     }
 
     pub fn get_cell_idx(&self, idx: usize) -> Cell {
-        self.cells[idx]
+        CELLS[self.cell_state_at(idx)]
+    }
+
+    /// The index into [`CELLS`] of the cell at `idx`.
+    fn cell_state_at(&self, idx: usize) -> usize {
+        ((self.player_one >> idx & 1) | (self.player_two >> idx & 1) << 1) as usize
+    }
+
+    /// Places `player`'s disc at `mv` and gives them every cell set in `flip_mask` (bit `i`
+    /// is cell `i`), in one step.
+    pub fn apply_move_and_flips(&mut self, mv: usize, flip_mask: u64, player: Player) {
+        let changed = flip_mask | 1 << mv;
+
+        match player {
+            Player::One => {
+                self.player_one |= changed;
+                self.player_two &= !changed;
+            }
+            Player::Two => {
+                self.player_two |= changed;
+                self.player_one &= !changed;
+            }
+        }
+    }
+
+    /// Reverses [`Board::apply_move_and_flips`]: empties `mv` and gives every cell set in
+    /// `flip_mask` back to `player`'s opponent.
+    pub fn undo_move_and_flips(&mut self, mv: usize, flip_mask: u64, player: Player) {
+        match player {
+            Player::One => {
+                self.player_one &= !(flip_mask | 1 << mv);
+                self.player_two |= flip_mask;
+            }
+            Player::Two => {
+                self.player_two &= !(flip_mask | 1 << mv);
+                self.player_one |= flip_mask;
+            }
+        }
     }
 
     pub fn on_board(&self, x: usize, y: usize) -> bool {
         x < WIDTH && y < HEIGHT
     }
 
-    pub fn iter(&self) -> std::slice::Iter<Cell> {
-        self.cells.iter()
+    /// Iterates over all cells in index order, rebuilding each one from the bitboards.
+    pub fn iter(&self) -> Cells<'_> {
+        Cells { board: self, idx: 0 }
+    }
+
+    /// Iterates over the indices of empty cells, in index order.
+    ///
+    /// Prefer this over collecting into a `Vec` when the cells are only walked once or the
+    /// walk may stop early, as nothing is allocated. Collect when the indices are needed
+    /// several times, or their count or random access is needed up front.
+    pub fn iter_empty(&self) -> impl Iterator<Item = usize> + '_ {
+        iter_bits(!(self.player_one | self.player_two))
+    }
+
+    /// Iterates over the indices of cells occupied by `player`, in index order. See
+    /// [`Board::iter_empty`] for when to prefer this over a `Vec`.
+    pub fn iter_occupied(&self, player: Player) -> impl Iterator<Item = usize> + '_ {
+        iter_bits(self.player_mask(player))
+    }
+
+    /// Converts the board into a row-major 2D array, indexed as `array[y][x]`.
+    pub fn to_array(&self) -> [[Cell; WIDTH]; HEIGHT] {
+        let mut array = [[Cell::Empty; WIDTH]; HEIGHT];
+
+        for (y, row) in array.iter_mut().enumerate() {
+            for (x, cell) in row.iter_mut().enumerate() {
+                *cell = self.get_cell(x, y);
+            }
+        }
+
+        array
+    }
+
+    /// Builds a board from a row-major 2D array, indexed as `array[y][x]`.
+    pub fn from_array(array: [[Cell; WIDTH]; HEIGHT]) -> Board {
+        let mut board = Board::new();
+
+        for (y, row) in array.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                board.set_cell(x, y, *cell);
+            }
+        }
+
+        board
+    }
+
+    /// A bitmask of the player's discs, with bit `i` set when cell `i` is theirs.
+    pub(crate) fn player_mask(&self, player: Player) -> u64 {
+        match player {
+            Player::One => self.player_one,
+            Player::Two => self.player_two,
+        }
+    }
+
+    /// Counts each player's discs as `(player_one, player_two)`, by counting the set bits of
+    /// their bitmasks. The two masks must be disjoint (no cell owned by both players), which
+    /// every `Board` guarantees.
+    pub fn population_count(&self) -> (u32, u32) {
+        (self.player_mask(Player::One).count_ones(), self.player_mask(Player::Two).count_ones())
+    }
+
+    /// Formats both players' disc bitmasks as hex, e.g. the starting position is
+    /// `p1=0x0000001008000000 p2=0x0000000810000000`.
+    pub fn fmt_as_hex(&self) -> String {
+        format!("p1={:#018x} p2={:#018x}", self.player_mask(Player::One), self.player_mask(Player::Two))
+    }
+
+    /// Builds a board from the hex bitmasks printed by [`Board::fmt_as_hex`]. The `0x` prefix
+    /// is optional.
+    pub fn from_hex(p1_hex: &str, p2_hex: &str) -> Result<Board> {
+        let parse = |hex: &str| {
+            u64::from_str_radix(hex.trim_start_matches("0x"), 16).map_err(|err| anyhow!("Invalid bitmask {}: {}", hex, err))
+        };
+
+        Board::from_masks(parse(p1_hex)?, parse(p2_hex)?)
+    }
+
+    /// Builds a board from each player's disc bitmask (bit `i` is cell `i`). Returns an error
+    /// if the masks overlap.
+    pub(crate) fn from_masks(player_one: u64, player_two: u64) -> Result<Board> {
+        if player_one & player_two != 0 {
+            Err(anyhow!("Bitmasks overlap: {:#018x}", player_one & player_two))?;
+        }
+
+        Ok(Board::from_bitboards(player_one, player_two))
+    }
+
+    /// Encodes the board as player one's bitboard then player two's, each as a little-endian
+    /// `u64`.
+    pub fn to_bytes(&self) -> [u8; 16] {
+        let mut bytes = [0; 16];
+        bytes[..8].copy_from_slice(&self.player_one.to_le_bytes());
+        bytes[8..].copy_from_slice(&self.player_two.to_le_bytes());
+        bytes
+    }
+
+    /// Decodes a board written by [`Board::to_bytes`]. Returns an error if the bitboards
+    /// overlap.
+    pub fn from_bytes(bytes: &[u8; 16]) -> Result<Board> {
+        let (player_one, player_two) = bytes.split_at(8);
+
+        Board::from_masks(
+            u64::from_le_bytes(player_one.try_into().unwrap()),
+            u64::from_le_bytes(player_two.try_into().unwrap()),
+        )
+    }
+
+    /// Moves every cell to where `sym` maps it. This covers all of the symmetry operations
+    /// below in one call.
+    pub fn apply_symmetry(&self, sym: Symmetry) -> Board {
+        let mut board = Board::new();
+
+        for (idx, cell) in self.iter().enumerate() {
+            board.set_cell_idx(apply_symmetry_to_index(idx, sym), *cell);
+        }
+
+        board
+    }
+
+    /// Mirrors the board left to right.
+    pub fn mirror_horizontal(&self) -> Board {
+        self.apply_symmetry(Symmetry::FlipH)
+    }
+
+    /// Mirrors the board top to bottom.
+    pub fn mirror_vertical(&self) -> Board {
+        self.apply_symmetry(Symmetry::FlipV)
+    }
+
+    pub fn rotate_90_clockwise(&self) -> Board {
+        self.apply_symmetry(Symmetry::Rot90)
+    }
+
+    pub fn rotate_180(&self) -> Board {
+        self.apply_symmetry(Symmetry::Rot180)
+    }
+
+    pub fn rotate_270_clockwise(&self) -> Board {
+        self.apply_symmetry(Symmetry::Rot270)
+    }
+
+    /// Mirrors the board along its main (top-left to bottom-right) diagonal.
+    pub fn transpose(&self) -> Board {
+        self.apply_symmetry(Symmetry::FlipDiag)
+    }
+
+    /// Groups a player's discs into orthogonally (4-)connected components.
+    ///
+    /// Components are ordered by their lowest cell index, and the indices within each
+    /// component are sorted.
+    pub fn connected_components(&self, player: Player) -> Vec<Vec<usize>> {
+        let mut visited = [false; SIZE];
+        let mut components = Vec::new();
+
+        for start in 0..SIZE {
+            if visited[start] || self.get_cell_idx(start) != Cell::Player(player) {
+                continue;
+            }
+
+            let mut component = Vec::new();
+            let mut queue = VecDeque::from([start]);
+            visited[start] = true;
+
+            while let Some(idx) = queue.pop_front() {
+                component.push(idx);
+
+                let (x, y) = (idx % WIDTH, idx / WIDTH);
+
+                for (x_dir, y_dir) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+                    let (x, y) = (x.wrapping_add_signed(x_dir), y.wrapping_add_signed(y_dir));
+
+                    if !self.on_board(x, y) {
+                        continue;
+                    }
+
+                    let neighbour = at_pos(x, y);
+
+                    if !visited[neighbour] && self.get_cell_idx(neighbour) == Cell::Player(player) {
+                        visited[neighbour] = true;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+
+            component.sort_unstable();
+            components.push(component);
+        }
+
+        components
+    }
+
+    /// The number of the up to 8 cells around `(x, y)` that hold a disc of either player.
+    pub fn count_adjacency(&self, x: usize, y: usize) -> u8 {
+        self.neighbours(x, y).filter(|idx| self.get_cell_idx(*idx) != Cell::Empty).count() as u8
+    }
+
+    /// The number of the up to 8 cells around `(x, y)` that hold one of `player`'s discs.
+    pub fn count_adjacency_by_player(&self, x: usize, y: usize, player: Player) -> u8 {
+        self.neighbours(x, y).filter(|idx| self.get_cell_idx(*idx) == Cell::Player(player)).count() as u8
+    }
+
+    /// The indices of the cells around `(x, y)` that are on the board.
+    fn neighbours(&self, x: usize, y: usize) -> impl Iterator<Item = usize> + '_ {
+        (-1..=1).flat_map(move |x_dir| (-1..=1).map(move |y_dir| (x_dir, y_dir)))
+            .filter(|direction| *direction != (0, 0))
+            .map(move |(x_dir, y_dir)| (x.wrapping_add_signed(x_dir), y.wrapping_add_signed(y_dir)))
+            .filter(|(x, y)| self.on_board(*x, *y))
+            .map(|(x, y)| at_pos(x, y))
+    }
+
+    /// `player`'s discs that can never be flipped again, as a bitboard (bit `i` is cell `i`).
+    ///
+    /// A disc is stable if, along each of the four lines through it (its row, column and two
+    /// diagonals), the line is full or one of its two neighbours on that line is off the
+    /// board or another stable disc of theirs. Stable discs are found by repeating this until
+    /// nothing more is added, starting from none. This misses some stable discs, but never
+    /// includes one that could be flipped.
+    pub fn stability_mask(&self, player: Player) -> u64 {
+        let own = self.player_mask(player);
+        let full_lines = self.full_line_masks();
+        let mut stable = 0;
+
+        loop {
+            let mut added = 0;
+
+            for idx in iter_bits(own & !stable) {
+                let (x, y) = (idx % WIDTH, idx / WIDTH);
+
+                let anchored = |x_dir: isize, y_dir: isize| {
+                    let (x, y) = (x.wrapping_add_signed(x_dir), y.wrapping_add_signed(y_dir));
+
+                    !self.on_board(x, y) || stable & 1 << at_pos(x, y) != 0
+                };
+
+                let is_stable = LINE_DIRECTIONS.iter().zip(full_lines).all(|((x_dir, y_dir), full)| {
+                    full & 1 << idx != 0 || anchored(*x_dir, *y_dir) || anchored(-x_dir, -y_dir)
+                });
+
+                if is_stable {
+                    added |= 1 << idx;
+                }
+            }
+
+            if added == 0 {
+                return stable;
+            }
+
+            stable |= added;
+        }
+    }
+
+    /// A cheaper, weaker form of [`Board::stability_mask`]: only `player`'s discs whose row
+    /// and column are both full, as a bitboard (bit `i` is cell `i`).
+    ///
+    /// No disc can be flipped through a full line, but a disc on a full row and column can
+    /// still be flipped along a diagonal, so both of its diagonals have to be full too for
+    /// the result to be safe. This makes it a subset of [`Board::stability_mask`].
+    ///
+    /// Discs anchored to a corner or edge don't count until their lines fill up, so it misses
+    /// many stable discs until late in the game.
+    pub fn weak_stability_mask(&self, player: Player) -> u64 {
+        self.full_line_masks().into_iter().fold(self.player_mask(player), |mask, full| mask & full)
+    }
+
+    /// For each of [`LINE_DIRECTIONS`], the cells whose line in that direction is full.
+    fn full_line_masks(&self) -> [u64; 4] {
+        let filled = self.player_one | self.player_two;
+        let line_mask = |line: &mut dyn Iterator<Item = usize>| line.fold(0u64, |mask, idx| mask | 1 << idx);
+        let full = |mask: u64| if filled & mask == mask { mask } else { 0 };
+
+        let rows = (0..HEIGHT).map(|y| full(line_mask(&mut row(y)))).fold(0, |mask, row| mask | row);
+        let cols = (0..WIDTH).map(|x| full(line_mask(&mut col(x)))).fold(0, |mask, col| mask | col);
+
+        // as in `longest_run`, every diagonal starts on the top row or on a side column
+        let diagonals = |x_dir: isize, side: usize| {
+            (0..WIDTH).map(|x| diagonal(x, 0, x_dir))
+                .chain((1..HEIGHT).map(|y| diagonal(side, y, x_dir)))
+                .map(|mut line| full(line_mask(&mut line)))
+                .fold(0, |mask, line| mask | line)
+        };
+
+        [rows, cols, diagonals(1, 0), diagonals(-1, WIDTH - 1)]
+    }
+
+    /// The length of the longest unbroken line of `player`'s discs along any row, column or
+    /// diagonal. It is `0` when they have no discs.
+    pub fn longest_run(&self, player: Player) -> usize {
+        let rows = (0..HEIGHT).map(|y| row(y).collect::<Vec<_>>());
+        let cols = (0..WIDTH).map(|x| col(x).collect::<Vec<_>>());
+
+        // every diagonal starts on the top row or, going down to the right, on the left column
+        // and, going down to the left, on the right column
+        let diagonals = (0..WIDTH).flat_map(|x| [diagonal(x, 0, 1), diagonal(x, 0, -1)])
+            .chain((1..HEIGHT).flat_map(|y| [diagonal(0, y, 1), diagonal(WIDTH - 1, y, -1)]))
+            .map(|line| line.collect::<Vec<_>>());
+
+        rows.chain(cols).chain(diagonals).map(|line| {
+            line.into_iter().fold((0, 0), |(longest, current), idx| {
+                let current = if self.get_cell_idx(idx) == Cell::Player(player) { current + 1 } else { 0 };
+
+                (longest.max(current), current)
+            }).0
+        }).max().unwrap_or(0)
+    }
+
+    /// The fraction of empty squares `player` could eventually reach: those connected to one
+    /// of their discs through a path of empty squares, searched breadth first from the discs
+    /// through all 8 neighbours. It is `1.0` when every empty square borders one of their
+    /// discs, and `0.0` on a full board.
+    pub fn accessibility_score(&self, player: Player) -> f32 {
+        let empty = self.iter_empty().count();
+
+        if empty == 0 {
+            return 0.0;
+        }
+
+        let mut visited = [false; SIZE];
+        let mut queue = self.iter_occupied(player).collect::<VecDeque<_>>();
+        let mut accessible = 0;
+
+        while let Some(idx) = queue.pop_front() {
+            let (x, y) = (idx % WIDTH, idx / WIDTH);
+
+            for x_dir in -1..=1 {
+                for y_dir in -1..=1 {
+                    let (x, y) = (x.wrapping_add_signed(x_dir), y.wrapping_add_signed(y_dir));
+
+                    if !self.on_board(x, y) {
+                        continue;
+                    }
+
+                    let neighbour = at_pos(x, y);
+
+                    if !visited[neighbour] && self.get_cell_idx(neighbour) == Cell::Empty {
+                        visited[neighbour] = true;
+                        accessible += 1;
+                        queue.push_back(neighbour);
+                    }
+                }
+            }
+        }
+
+        accessible as f32 / empty as f32
+    }
+}
+
+impl Board {
+    const BOARD_COLOR: [u8; 4] = [0, 128, 0, 255];
+    const GRID_COLOR: [u8; 4] = [0, 80, 0, 255];
+    const MOVE_COLOR: [u8; 4] = [40, 40, 40, 255];
+
+    /// Renders the board as raw RGBA pixel data (4 bytes per pixel, row by row) for an image
+    /// of `WIDTH * cell_size` by `HEIGHT * cell_size` pixels.
+    ///
+    /// Squares are green with a darker grid, player one's discs are black and player two's are
+    /// white. Each index in `moves` is marked with a dotted circle.
+    pub fn to_image_bytes(&self, cell_size: usize, moves: &[usize]) -> Vec<u8> {
+        let (image_width, image_height) = (WIDTH * cell_size, HEIGHT * cell_size);
+        let mut bytes = Vec::with_capacity(image_width * image_height * 4);
+
+        let radius = cell_size as f32 * 0.4;
+
+        for py in 0..image_height {
+            for px in 0..image_width {
+                let (x, y) = (px / cell_size, py / cell_size);
+                let (local_x, local_y) = (px % cell_size, py % cell_size);
+
+                let dx = local_x as f32 + 0.5 - cell_size as f32 / 2.0;
+                let dy = local_y as f32 + 0.5 - cell_size as f32 / 2.0;
+                let distance = (dx * dx + dy * dy).sqrt();
+
+                let color = match self.get_cell(x, y) {
+                    _ if local_x == 0 || local_y == 0 => Self::GRID_COLOR,
+                    Cell::Player(Player::One) if distance <= radius => [0, 0, 0, 255],
+                    Cell::Player(Player::Two) if distance <= radius => [255, 255, 255, 255],
+                    Cell::Empty if moves.contains(&at_pos(x, y)) && (distance - radius).abs() < 1.0 => {
+                        // split the outline into 16 arcs and only draw every other one
+                        let arc = ((dy.atan2(dx) + std::f32::consts::PI) / std::f32::consts::TAU * 16.0) as usize;
+
+                        if arc.is_multiple_of(2) { Self::MOVE_COLOR } else { Self::BOARD_COLOR }
+                    }
+                    _ => Self::BOARD_COLOR,
+                };
+
+                bytes.extend_from_slice(&color);
+            }
+        }
+
+        bytes
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TryFrom<[[char; WIDTH]; HEIGHT]> for Board {
+    type Error = anyhow::Error;
+
+    /// Builds a board from rows of cells written as for [`Cell::try_from`], indexed as
+    /// `array[y][x]` like [`Board::from_array`].
+    fn try_from(array: [[char; WIDTH]; HEIGHT]) -> Result<Board> {
+        let mut cells = [[Cell::Empty; WIDTH]; HEIGHT];
+
+        for (y, row) in array.iter().enumerate() {
+            for (x, character) in row.iter().enumerate() {
+                cells[y][x] = Cell::try_from(*character).map_err(|_| anyhow!("Invalid character {:?} at {}", character, Move::new(x, y).to_coord()))?;
+            }
+        }
+
+        Ok(Board::from_array(cells))
+    }
+}
+
+/// An iterator over a [`Board`]'s cells in index order, returned by [`Board::iter`]. Nothing
+/// is allocated: each cell is read from the bitboards as it is reached.
+#[derive(Clone, Debug)]
+pub struct Cells<'a> {
+    board: &'a Board,
+    idx: usize,
+}
+
+impl<'a> Iterator for Cells<'a> {
+    type Item = &'a Cell;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.idx >= SIZE {
+            return None;
+        }
+
+        let cell = &CELLS[self.board.cell_state_at(self.idx)];
+        self.idx += 1;
+
+        Some(cell)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = SIZE - self.idx;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Cells<'_> {}
+
+impl<'a> IntoIterator for &'a Board {
+    type Item = &'a Cell;
+    type IntoIter = Cells<'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl fmt::Display for Player {
+    /// `X` or `O`, padded to any requested width.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_char(), f)
+    }
+}
+
+impl fmt::Display for Cell {
+    /// `X`, `O` or `-`, as [`Cell::to_char`].
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.to_char(), f)
     }
 }
 
@@ -85,7 +993,7 @@ impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for y in 0..HEIGHT {
             for x in 0..WIDTH {
-                write!(f, "{}", self.get_cell(x, y).to_char())?;
+                write!(f, "{}", self.get_cell(x, y))?;
             }
             writeln!(f)?;
         }
@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use crate::{Game, board::Symmetry};
+
+/// Added to the scores of moves returned from an [`OpeningBook`] by
+/// [`Game::solve_with_book`], marking them as book moves. It is larger than any solver score,
+/// so book moves always rank first; subtract it to recover the stored score.
+pub const BOOK_SCORE: isize = 1000;
+
+/// Known good moves for positions, with their scores.
+#[derive(Clone, Debug, Default)]
+pub struct OpeningBook {
+    entries: HashMap<Game, Vec<(isize, usize)>>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores the scored moves for a position, replacing any previous entry.
+    pub fn insert(&mut self, game: Game, moves: Vec<(isize, usize)>) {
+        self.entries.insert(game, moves);
+    }
+
+    /// Looks up the scored moves for a position. Positions symmetric to a stored one are
+    /// found too, with their moves mapped onto the queried board.
+    pub fn get(&self, game: &Game) -> Option<Vec<(isize, usize)>> {
+        Symmetry::ALL.into_iter().zip(game.make_symmetric_positions()).find_map(|(symmetry, symmetric)| {
+            self.entries.get(&symmetric).map(|moves| {
+                moves.iter().map(|(score, mv)| (*score, Game::map_move_to_original(*mv, symmetry))).collect()
+            })
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
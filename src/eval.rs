@@ -0,0 +1,75 @@
+use crate::{Game, board::{get_moves_bitboard, Cell, Player, SIZE}, solve};
+
+/// A heuristic score for positions where a depth-limited search stops, from the point of
+/// view of the player to move. Plain functions and closures taking a [`Game`] work too.
+pub trait EvaluationFn: Send + Sync {
+    fn evaluate(&self, game: &Game) -> isize;
+}
+
+impl<F: Fn(&Game) -> isize + Send + Sync> EvaluationFn for F {
+    fn evaluate(&self, game: &Game) -> isize {
+        self(game)
+    }
+}
+
+/// The number of discs the player to move has more than the opponent, as
+/// [`solve::disc_difference`].
+#[derive(Copy, Clone, Debug, Default)]
+pub struct DiscDifferenceEval;
+
+impl EvaluationFn for DiscDifferenceEval {
+    fn evaluate(&self, game: &Game) -> isize {
+        solve::disc_difference(game)
+    }
+}
+
+/// The sum of [`WeightedDiscEval::WEIGHTS`] over the player to move's discs, minus the sum
+/// over the opponent's: Player One's total minus Player Two's when Player One is to move,
+/// and the reverse otherwise.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct WeightedDiscEval;
+
+impl WeightedDiscEval {
+    /// The value of a disc on each square, in index order: corners `120`, X-squares and
+    /// C-squares `-20`, the rest of the edge `20`, and the interior `3` to `5`, rising
+    /// towards the centre. Corners can never be flipped, while the squares next to them hand
+    /// the corner to the opponent.
+    ///
+    /// This is a simplified form of the classic weight table from Peter Norvig's *Paradigms
+    /// of Artificial Intelligence Programming* (1992), chapter 18.
+    #[rustfmt::skip]
+    pub const WEIGHTS: [isize; SIZE] = [
+        120, -20,  20,  20,  20,  20, -20, 120,
+        -20, -20,   3,   3,   3,   3, -20, -20,
+         20,   3,   4,   4,   4,   4,   3,  20,
+         20,   3,   4,   5,   5,   4,   3,  20,
+         20,   3,   4,   5,   5,   4,   3,  20,
+         20,   3,   4,   4,   4,   4,   3,  20,
+        -20, -20,   3,   3,   3,   3, -20, -20,
+        120, -20,  20,  20,  20,  20, -20, 120,
+    ];
+}
+
+impl EvaluationFn for WeightedDiscEval {
+    fn evaluate(&self, game: &Game) -> isize {
+        let weight = |player: Player| Self::WEIGHTS.iter().zip(game.iter())
+            .filter(|(_, cell)| *cell == Cell::Player(player))
+            .map(|(weight, _)| weight)
+            .sum::<isize>();
+
+        weight(game.current_player) - weight(game.current_player.opponent())
+    }
+}
+
+/// The number of legal moves the player to move has more than the opponent would have.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct MobilityEval;
+
+impl EvaluationFn for MobilityEval {
+    fn evaluate(&self, game: &Game) -> isize {
+        let (player, opponent) = (game.board().player_mask(game.current_player), game.board().player_mask(game.current_player.opponent()));
+        let empty = !(player | opponent);
+
+        get_moves_bitboard(player, opponent, empty).count_ones() as isize - get_moves_bitboard(opponent, player, empty).count_ones() as isize
+    }
+}
@@ -0,0 +1,191 @@
+use anyhow::{Result, anyhow};
+
+/// A parsed JSON value. Only what the save format needs is kept: numbers and booleans are
+/// parsed (so files with them still load) but their values are not stored.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Value {
+    Null,
+    Bool,
+    Number,
+    String(String),
+    Array(Vec<Value>),
+    Object(Vec<(String, Value)>),
+}
+
+impl Value {
+    /// The value of `key`, if this is an object containing it.
+    pub(crate) fn get(&self, key: &str) -> Option<&Value> {
+        match self {
+            Value::Object(fields) => fields.iter().find(|(name, _)| name == key).map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a complete JSON document, which may be surrounded by whitespace.
+pub(crate) fn parse(string: &str) -> Result<Value> {
+    let mut parser = Parser { chars: string.char_indices().peekable() };
+
+    let value = parser.value()?;
+    parser.skip_whitespace();
+
+    match parser.chars.next() {
+        Some((offset, character)) => Err(anyhow!("Unexpected {:?} after the JSON value at byte {}", character, offset)),
+        None => Ok(value),
+    }
+}
+
+/// Writes `string` as a JSON string literal.
+pub(crate) fn quote(string: &str) -> String {
+    let mut quoted = String::from('"');
+
+    for character in string.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            character if character.is_control() => quoted.push_str(&format!("\\u{:04x}", character as u32)),
+            character => quoted.push(character),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::CharIndices<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, character)| character.is_ascii_whitespace()).is_some() {}
+    }
+
+    fn next(&mut self) -> Result<char> {
+        self.chars.next().map(|(_, character)| character).ok_or(anyhow!("Unexpected end of JSON"))
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespace();
+
+        match self.next()? {
+            character if character == expected => Ok(()),
+            character => Err(anyhow!("Expected {:?}, found {:?}", expected, character)),
+        }
+    }
+
+    fn keyword(&mut self, keyword: &str, value: Value) -> Result<Value> {
+        for expected in keyword.chars() {
+            if self.next()? != expected {
+                Err(anyhow!("Invalid keyword, expected {}", keyword))?;
+            }
+        }
+
+        Ok(value)
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        self.skip_whitespace();
+
+        match self.chars.peek().map(|(_, character)| *character) {
+            None => Err(anyhow!("Unexpected end of JSON")),
+            Some('n') => self.keyword("null", Value::Null),
+            Some('t') => self.keyword("true", Value::Bool),
+            Some('f') => self.keyword("false", Value::Bool),
+            Some('"') => Ok(Value::String(self.string()?)),
+            Some('[') => self.array(),
+            Some('{') => self.object(),
+            Some('-' | '0'..='9') => self.number(),
+            Some(character) => Err(anyhow!("Unexpected {:?} in JSON", character)),
+        }
+    }
+
+    fn number(&mut self) -> Result<Value> {
+        let mut number = String::new();
+
+        while let Some((_, character)) = self.chars.next_if(|(_, character)| matches!(character, '-' | '+' | '.' | 'e' | 'E' | '0'..='9')) {
+            number.push(character);
+        }
+
+        number.parse::<f64>().map_err(|_| anyhow!("Invalid number: {}", number))?;
+
+        Ok(Value::Number)
+    }
+
+    fn string(&mut self) -> Result<String> {
+        self.expect('"')?;
+
+        let mut string = String::new();
+
+        loop {
+            match self.next()? {
+                '"' => return Ok(string),
+                '\\' => string.push(match self.next()? {
+                    '"' => '"',
+                    '\\' => '\\',
+                    '/' => '/',
+                    'b' => '\u{8}',
+                    'f' => '\u{c}',
+                    'n' => '\n',
+                    'r' => '\r',
+                    't' => '\t',
+                    'u' => {
+                        let hex = (0..4).map(|_| self.next()).collect::<Result<String>>()?;
+                        u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32).ok_or(anyhow!("Invalid unicode escape: {}", hex))?
+                    }
+                    character => Err(anyhow!("Invalid escape: \\{}", character))?,
+                }),
+                character if character.is_control() => Err(anyhow!("Unescaped control character in string"))?,
+                character => string.push(character),
+            }
+        }
+    }
+
+    fn array(&mut self) -> Result<Value> {
+        self.expect('[')?;
+        self.skip_whitespace();
+
+        let mut values = Vec::new();
+
+        if self.chars.next_if(|(_, character)| *character == ']').is_some() {
+            return Ok(Value::Array(values));
+        }
+
+        loop {
+            values.push(self.value()?);
+            self.skip_whitespace();
+
+            match self.next()? {
+                ',' => continue,
+                ']' => return Ok(Value::Array(values)),
+                character => Err(anyhow!("Expected ',' or ']' in array, found {:?}", character))?,
+            }
+        }
+    }
+
+    fn object(&mut self) -> Result<Value> {
+        self.expect('{')?;
+        self.skip_whitespace();
+
+        let mut fields = Vec::new();
+
+        if self.chars.next_if(|(_, character)| *character == '}').is_some() {
+            return Ok(Value::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let key = self.string()?;
+            self.expect(':')?;
+            fields.push((key, self.value()?));
+            self.skip_whitespace();
+
+            match self.next()? {
+                ',' => continue,
+                '}' => return Ok(Value::Object(fields)),
+                character => Err(anyhow!("Expected ',' or '}}' in object, found {:?}", character))?,
+            }
+        }
+    }
+}
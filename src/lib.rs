@@ -1,53 +1,287 @@
+#[cfg(feature = "arena")]
+pub mod arena;
 pub mod board;
+pub mod book;
+pub mod eval;
+mod json;
+pub mod record;
 pub mod solve;
+pub mod wthor;
+pub mod zobrist;
 
-use std::fmt;
+use std::{collections::HashMap, fmt};
 
-use board::{Board, Cell, Player, at_pos, HEIGHT, WIDTH};
+use board::{Board, Cell, CellType, Move, Player, Symmetry, at_pos, LEFT_COLUMN, flips_bitboard, get_moves_bitboard, iter_bits, HEIGHT, WIDTH, SIZE};
+use book::{OpeningBook, BOOK_SCORE};
+use record::{GameMetadata, GameRecord};
+use zobrist::{cell_state, side_key, ZobristHasher, ZOBRIST_TABLE};
 use anyhow::{Result, anyhow};
 
+/// Errors with details callers may want to inspect, reached by downcasting an
+/// [`anyhow::Error`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ReversiError {
+    /// The position has more empty squares than the search allows.
+    TooManyEmpties { actual: usize, max: usize },
+}
+
+impl fmt::Display for ReversiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReversiError::TooManyEmpties { actual, max } => write!(f, "Too many empty squares: {} > {}", actual, max),
+        }
+    }
+}
+
+impl std::error::Error for ReversiError {}
+
+/// How a finished game ended.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GameResult {
+    /// The player with more discs won.
+    Win(Player),
+    /// Both players have the same number of discs.
+    Draw,
+}
+
+/// A single position encoded for training AlphaZero-style networks.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TrainingSample {
+    /// Each cell as an `(is_current_player, is_opponent)` pair, in cell index order.
+    pub features: [f32; 2 * SIZE],
+    /// The solver score for the player to move, normalized to `[-1, 1]`.
+    pub value: f32,
+    /// Softmax over the solver scores of each legal move; illegal moves are `0`.
+    pub policy: [f32; SIZE],
+}
+
+const DIRECTIONS: &[(isize, isize)] = &[
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+];
+
+const CORNERS: [usize; 4] = [0, WIDTH - 1, SIZE - WIDTH, SIZE - 1];
+
+/// Weights for the features combined by [`Game::eval_with_weights`]. Every feature is
+/// measured as the current player's value minus the opponent's.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EvalWeights {
+    /// Disc difference.
+    pub disc_count: f32,
+    /// Difference in the number of legal moves.
+    pub mobility: f32,
+    /// Difference in occupied corners.
+    pub corners: f32,
+    /// Difference in edge discs anchored to an owned corner, which can never be flipped.
+    pub stability: f32,
+    /// Difference in discs bordering an empty square. Usually negative, as frontier discs
+    /// give the opponent moves.
+    pub frontier: f32,
+    /// `1` if the current player is due the last move of the game (an odd number of empty
+    /// squares), `-1` otherwise.
+    pub parity: f32,
+}
+
+impl Default for EvalWeights {
+    fn default() -> Self {
+        EvalWeights {
+            disc_count: 1.0,
+            mobility: 5.0,
+            corners: 25.0,
+            stability: 10.0,
+            frontier: -3.0,
+            parity: 2.0,
+        }
+    }
+}
+
+impl EvalWeights {
+    fn to_array(self) -> [f32; 6] {
+        [self.disc_count, self.mobility, self.corners, self.stability, self.frontier, self.parity]
+    }
+
+    fn from_array([disc_count, mobility, corners, stability, frontier, parity]: [f32; 6]) -> Self {
+        EvalWeights { disc_count, mobility, corners, stability, frontier, parity }
+    }
+}
+
+/// One move or pass in a [`Game`]'s history.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct HistoryEntry {
+    /// The cell played, or `None` for a pass.
+    pub move_idx: Option<usize>,
+    /// The player who moved or passed.
+    pub player: Player,
+    /// The discs the move flipped (bit `i` is cell `i`), or `0` for a pass.
+    pub flips: u64,
+}
+
 /// A game struct representing the current Reversi game state.
-#[derive(Clone, PartialEq)]
+///
+/// Games compare equal (and hash the same) when their positions do: the same board and
+/// player to move, however they were reached.
 pub struct Game {
     board: board::Board,
     current_player: Player,
+    /// The Zobrist hash of `board` and `current_player`, kept up to date as they change.
+    hash: u64,
+    /// Every move and pass played in this game, oldest first.
+    history: Vec<HistoryEntry>,
 }
 
 impl Game {
     // TODO: this will be horrendously inefficient, however, i want to get test cases in place first,
     // so i'm doing rudimentary solutions for me to work out later
-    pub fn moves(&self) -> Vec<usize> {
-        let mut moves = Vec::new();
+    pub fn moves(&self) -> Vec<Move> {
+        self.move_indices().into_iter().map(Move::from_index).collect()
+    }
 
-        // loop through all cells and check if they are valid moves
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
-                if let Some(_) = self.is_valid_move(x, y) {
-                    moves.push(at_pos(x, y));
-                }
-            }
-        }
+    /// The legal moves as cell indices, in the same order as [`Game::moves`]. This is the
+    /// lower-level form used with [`Game::play_idx`] and bitmasks.
+    pub fn move_indices(&self) -> Vec<usize> {
+        let legal = self.legal_moves_mask();
+
+        // column by column, top to bottom within each
+        (0..WIDTH).flat_map(|x| iter_bits(legal & LEFT_COLUMN << x)).collect()
+    }
+
+    /// Every legal move, in the same order as [`Game::moves`], with the discs it would flip.
+    pub fn possible_moves_with_flips(&self) -> Vec<(usize, Vec<usize>)> {
+        self.move_indices().into_iter().map(|idx| (idx, self.flips(idx))).collect()
+    }
+
+    /// The number of discs each legal move flips, as `(flip_count, move)` pairs sorted by flip
+    /// count from most to fewest, with ties in move order.
+    pub fn count_captures_for_all_moves(&self) -> Vec<(usize, usize)> {
+        let mut captures = self.possible_moves_with_flips().into_iter().map(|(idx, flips)| (flips.len(), idx)).collect::<Vec<_>>();
+
+        captures.sort_by(|(a_flips, a_idx), (b_flips, b_idx)| b_flips.cmp(a_flips).then(a_idx.cmp(b_idx)));
+
+        captures
+    }
+
+    /// Whether both the current player and the opponent have exactly one legal move here, so
+    /// the position can be played forward without any search.
+    pub fn is_ladder(&self) -> bool {
+        let mut opponent = self.clone();
+        opponent.swap_players_unchecked();
 
-        moves
+        self.move_indices().len() == 1 && opponent.move_indices().len() == 1
     }
 
+    /// The legal moves of the current player as a bitmask, with bit `i` set for cell `i`.
+    pub fn legal_moves_mask(&self) -> u64 {
+        let player = self.board.player_mask(self.current_player);
+        let opponent = self.board.player_mask(self.current_player.opponent());
+
+        get_moves_bitboard(player, opponent, !(player | opponent))
+    }
+
+    /// Hands the turn to the opponent without checking that it is legal.
+    #[deprecated(note = "use `Game::pass`, which only allows forced passes")]
     pub fn swap_players(&mut self) {
-        self.current_player = self.current_player.opponent();
+        self.swap_players_unchecked();
     }
 
-    fn winning_player(&self) -> Option<Player> {
-        let mut player_one_count = 0;
-        let mut player_two_count = 0;
+    /// Passes the turn to the opponent. Passing is only allowed when forced, so this returns
+    /// an error if the current player has a legal move.
+    pub fn pass(&mut self) -> Result<()> {
+        if self.legal_moves_mask() != 0 {
+            Err(anyhow!("Cannot pass with {} legal moves available", self.legal_moves_mask().count_ones()))?;
+        }
 
-        for x in 0..WIDTH {
-            for y in 0..HEIGHT {
-                match self.board.get_cell(x, y) {
-                    Cell::Player(Player::One) => player_one_count += 1,
-                    Cell::Player(Player::Two) => player_two_count += 1,
-                    _ => (),
-                }
-            }
+        self.history.push(HistoryEntry { move_idx: None, player: self.current_player, flips: 0 });
+
+        self.swap_players_unchecked();
+        Ok(())
+    }
+
+    /// Hands the turn to the opponent without placing a disc, for null-move pruning.
+    ///
+    /// Unlike [`Game::pass`] this never checks that the current player had to pass, so the
+    /// result may be a position that cannot come up in a legal game. It is only meant for
+    /// searches that want to see what the opponent could do with a free move.
+    pub fn apply_null_move(&mut self) {
+        self.swap_players_unchecked();
+    }
+
+    /// Whether a null move search is unlikely to mislead here: the current player has moves
+    /// to choose from, neither side can win the game on the spot, and the endgame (where
+    /// being forced to move is often what decides the game) has not been reached.
+    pub fn is_null_move_safe(&self) -> bool {
+        if self.legal_moves_mask() == 0 || self.is_theoretically_solved() {
+            return false;
+        }
+
+        let mut opponent = self.clone();
+        opponent.apply_null_move();
+
+        self.immediate_wins() == 0 && opponent.immediate_wins() == 0
+    }
+
+    /// Hands the turn to the opponent, whether or not the current player could move, without
+    /// recording anything in the history. Used to look at a position from the opponent's
+    /// side; real passes go through [`Game::pass`].
+    pub(crate) fn swap_players_unchecked(&mut self) {
+        self.set_current_player(self.current_player.opponent());
+    }
+
+    /// Hands the turn to `player`, keeping the hash up to date.
+    fn set_current_player(&mut self, player: Player) {
+        self.hash ^= side_key(self.current_player) ^ side_key(player);
+        self.current_player = player;
+    }
+
+    /// Every position the current player can reach in one turn: one per legal move, or, if
+    /// they have to pass, the same board with the opponent to move. Finished games have none.
+    pub fn play_all_legal_moves(&self) -> Vec<Game> {
+        self.legal_turns().into_iter().map(|turn| {
+            let mut new_game = self.clone();
+            new_game.play_turn(turn).expect("legal turns can be played");
+            new_game
+        }).collect()
+    }
+
+    /// The turns behind [`Game::play_all_legal_moves`], in the same order: `Some(index)` for
+    /// each legal move, or a lone `None` if the current player has to pass. Finished games
+    /// have none. Searches take these with [`Game::play_turn`] and [`Game::undo`] rather than
+    /// cloning every child.
+    pub fn legal_turns(&self) -> Vec<Option<usize>> {
+        let moves = self.move_indices();
+
+        if moves.is_empty() {
+            return if self.is_game_over() { Vec::new() } else { vec![None] };
+        }
+
+        moves.into_iter().map(Some).collect()
+    }
+
+    /// Plays `Some(index)` with [`Game::play_idx`], or passes for `None`.
+    pub fn play_turn(&mut self, turn: Option<usize>) -> Result<()> {
+        match turn {
+            Some(index) => self.play_idx(index),
+            None => self.pass(),
         }
+    }
+
+    /// The number of discs `player` has on the board.
+    pub fn count_discs_for(&self, player: Player) -> usize {
+        self.board.player_mask(player).count_ones() as usize
+    }
+
+    /// The disc counts as `(player_one, player_two)`, at any point in the game.
+    pub fn score(&self) -> (usize, usize) {
+        (self.count_discs_for(Player::One), self.count_discs_for(Player::Two))
+    }
+
+    fn winning_player(&self) -> Option<Player> {
+        let (player_one_count, player_two_count) = self.score();
 
         if player_one_count > player_two_count {
             Some(Player::One)
@@ -58,12 +292,148 @@ impl Game {
         }
     }
 
+    /// Whether neither player has a legal move left, so the game has ended.
+    pub fn is_game_over(&self) -> bool {
+        if self.board.is_full() {
+            return true;
+        }
+
+        let (player_one, player_two) = self.board.to_bitboards();
+        let empty = !(player_one | player_two);
+
+        get_moves_bitboard(player_one, player_two, empty) == 0 && get_moves_bitboard(player_two, player_one, empty) == 0
+    }
+
+    /// How the game ended, or `None` while it is still in progress.
+    pub fn result(&self) -> Option<GameResult> {
+        if !self.is_game_over() {
+            return None;
+        }
+
+        Some(match self.winning_player() {
+            Some(player) => GameResult::Win(player),
+            None => GameResult::Draw,
+        })
+    }
+
+    /// Describes, in plain words, whether the current player has to pass and why, along with
+    /// the current disc counts.
+    pub fn explain_pass(&self) -> String {
+        let describe = |player: Player| match player {
+            Player::One => "Player X (Black)",
+            Player::Two => "Player O (White)",
+        };
+
+        let mut opponent = self.clone();
+        opponent.swap_players_unchecked();
+
+        let moves = self.move_indices().len();
+        let opponent_moves = opponent.move_indices().len();
+
+        let plural = |count: usize| if count == 1 { "move" } else { "moves" };
+
+        let explanation = if moves > 0 {
+            format!("{} does not need to pass: {} legal {}.", describe(self.current_player), moves, plural(moves))
+        } else if opponent_moves > 0 {
+            format!("{} must pass: no legal moves. Opponent has {} legal {}.", describe(self.current_player), opponent_moves, plural(opponent_moves))
+        } else {
+            format!("{} must pass: no legal moves, and neither does the opponent, so the game is over.", describe(self.current_player))
+        };
+
+        let (player_one, player_two) = self.score();
+
+        format!("{explanation} Discs: X {player_one}, O {player_two}.")
+    }
+
+    /// A quick check of conditions every position reached from the standard start must meet.
+    /// Passing it does not prove the position is reachable.
+    ///
+    /// The four centre squares start occupied and a disc is never removed, so they must all
+    /// hold a disc (of either colour, as they may have been flipped). Every move is also placed
+    /// next to an existing disc, so all discs must be connected to the centre through their
+    /// eight neighbours.
+    pub fn is_reachable_from_standard_start(&self) -> bool {
+        let centre = [at_pos(3, 3), at_pos(4, 3), at_pos(3, 4), at_pos(4, 4)];
+
+        if centre.iter().any(|idx| self.board.get_cell_idx(*idx) == Cell::Empty) {
+            return false;
+        }
+
+        let mut connected = [false; SIZE];
+        let mut stack = centre.to_vec();
+
+        for idx in &centre {
+            connected[*idx] = true;
+        }
+
+        while let Some(idx) = stack.pop() {
+            let (x, y) = (idx % WIDTH, idx / WIDTH);
+
+            for (x_dir, y_dir) in DIRECTIONS {
+                let (x, y) = (x.wrapping_add_signed(*x_dir), y.wrapping_add_signed(*y_dir));
+
+                if self.board.on_board(x, y) && !connected[at_pos(x, y)] && self.board.get_cell(x, y) != Cell::Empty {
+                    connected[at_pos(x, y)] = true;
+                    stack.push(at_pos(x, y));
+                }
+            }
+        }
+
+        self.board.total_moves() == connected.iter().filter(|connected| **connected).count()
+    }
+
+    /// Returns the final disc difference (player one minus player two) if the game is over,
+    /// or `None` if it is still in progress.
+    ///
+    /// This follows the standard scoring convention: any squares left empty at the end of
+    /// the game are counted towards the winner. A drawn game always scores `0`.
+    pub fn terminal_score(&self) -> Option<isize> {
+        if !self.is_game_over() {
+            return None;
+        }
+
+        let (player_one, player_two) = self.score();
+        let (player_one, player_two) = (player_one as isize, player_two as isize);
+        let empty = SIZE as isize - player_one - player_two;
+
+        let score = player_one - player_two;
+
+        Some(match score.signum() {
+            1 => score + empty,
+            -1 => score - empty,
+            _ => 0,
+        })
+    }
+
+    /// A provable `(lower, upper)` bound on the solver's score (the final disc difference for
+    /// the current player), found without searching.
+    ///
+    /// Discs in an unbroken edge run from an owned corner can never be flipped, so each side
+    /// keeps at least those to the end. As the final score counts empty squares towards the
+    /// winner, it is at most `64 - 2 * opponent_stable` and at least `2 * own_stable - 64`.
+    /// A finished game is bounded by its exact score.
+    pub fn score_bounds(&self) -> (isize, isize) {
+        if let Some(score) = self.terminal_score() {
+            let score = match self.current_player {
+                Player::One => score,
+                Player::Two => -score,
+            };
+
+            return (score, score);
+        }
+
+        let own_stable = self.edge_stable_discs(self.current_player) as isize;
+        let opponent_stable = self.edge_stable_discs(self.current_player.opponent()) as isize;
+
+        (2 * own_stable - SIZE as isize, SIZE as isize - 2 * opponent_stable)
+    }
+
     pub fn is_winning_move(&self, x: usize, y: usize, player: Player) -> Result<bool> {
         let mut new_game = self.clone();
 
         new_game.play(x, y)?;
 
-        Ok(new_game.winning_player() == Some(player) && new_game.moves().is_empty())
+        Ok(new_game.winning_player() == Some(player) && new_game.move_indices().is_empty())
     }
 
     pub fn is_winning_move_idx(&self, index: usize, player: Player) -> Result<bool> {
@@ -72,78 +442,57 @@ impl Game {
         self.is_winning_move(x, y, player)
     }
 
-    fn is_valid_move(&self, x_init: usize, y_init: usize) -> Option<Vec<usize>> {
-        let cell = self.board.get_cell(x_init, y_init);
+    fn is_valid_move(&self, x: usize, y: usize) -> Option<Vec<usize>> {
+        let idx = at_pos(x, y);
 
-        if cell != Cell::Empty {
+        if self.legal_moves_mask() & 1 << idx == 0 {
             return None;
         }
 
-        let opposing_tile = Cell::Player(self.current_player.opponent());
-
-        let mut tiles_to_flip: Vec<usize> = Vec::new();
-
-        let directions: &[(isize, isize)] = &[
-            (0, 1),
-            (1, 1),
-            (1, 0),
-            (1, -1),
-            (0, -1),
-            (-1, -1),
-            (-1, 0),
-            (-1, 1),
-        ];
-
-        for (x_dir, y_dir) in directions {
-            let mut x = x_init;
-            let mut y = y_init;
-
-            x = x.wrapping_add_signed(*x_dir);
-            y = y.wrapping_add_signed(*y_dir);
+        Some(self.flips(idx))
+    }
 
-            if !self.board.on_board(x, y) || self.board.get_cell(x, y) != opposing_tile {
-                continue;
-            }
+    /// Whether the current player may place a disc at `idx`, stopping at the first direction
+    /// that captures anything.
+    fn is_legal_move(&self, idx: usize) -> bool {
+        if self.board.get_cell_idx(idx) != Cell::Empty {
+            return false;
+        }
 
-            x = x.wrapping_add_signed(*x_dir);
-            y = y.wrapping_add_signed(*y_dir);
+        let opponent = Cell::Player(self.current_player.opponent());
 
-            if !self.board.on_board(x, y) {
-                continue;
-            }
+        DIRECTIONS.iter().any(|(x_dir, y_dir)| {
+            let (mut x, mut y) = (idx % WIDTH, idx / WIDTH);
+            let mut captured = false;
 
-            while self.board.get_cell(x, y) == opposing_tile {
-                x = x.wrapping_add_signed(*x_dir);
-                y = y.wrapping_add_signed(*y_dir);
+            loop {
+                (x, y) = (x.wrapping_add_signed(*x_dir), y.wrapping_add_signed(*y_dir));
 
                 if !self.board.on_board(x, y) {
-                    break;
+                    return false;
                 }
-            }
 
-            if !self.board.on_board(x, y) {
-                continue;
+                match self.board.get_cell(x, y) {
+                    cell if cell == opponent => captured = true,
+                    Cell::Empty => return false,
+                    Cell::Player(_) => return captured,
+                }
             }
+        })
+    }
 
-            if self.board.get_cell(x, y) == Cell::Player(self.current_player) {
-                loop {
-                    x = x.checked_add_signed(-*x_dir).unwrap();
-                    y = y.checked_add_signed(-*y_dir).unwrap();
+    /// The number of legal moves, counted square by square without building the move list.
+    /// Despite the name this is exact, always matching `moves().len()`.
+    pub fn count_legal_moves_naive(&self) -> usize {
+        (0..SIZE).filter(|idx| self.is_legal_move(*idx)).count()
+    }
 
-                    if x == x_init && y == y_init {
-                        break;
-                    }
+    /// The discs placing the current player's disc at `idx` would flip, in index order.
+    fn flips(&self, idx: usize) -> Vec<usize> {
+        let player = self.board.player_mask(self.current_player);
+        let opponent = self.board.player_mask(self.current_player.opponent());
 
-                    tiles_to_flip.push(at_pos(x, y));
-                }
-            }
-        }
-        
-        if tiles_to_flip.is_empty() {
-            None
-        } else {
-            Some(tiles_to_flip)
-        }
+        iter_bits(flips_bitboard(idx, player, opponent)).collect()
     }
 
     pub fn new() -> Game {
@@ -155,25 +504,138 @@ impl Game {
         board.set_cell(3, 4, Cell::Player(Player::Two));
         board.set_cell(4, 3, Cell::Player(Player::Two));
 
+        Game::from_board(board, Player::One)
+    }
+
+    fn from_board(board: Board, current_player: Player) -> Game {
         Game {
+            hash: board.compute_hash() ^ side_key(current_player),
             board,
-            current_player: Player::One,
+            current_player,
+            history: Vec::new(),
         }
     }
 
+    /// Sets a cell, keeping the hash up to date.
+    fn set_cell_idx(&mut self, idx: usize, cell: Cell) {
+        self.hash ^= ZOBRIST_TABLE[idx][cell_state(self.board.get_cell_idx(idx))] ^ ZOBRIST_TABLE[idx][cell_state(cell)];
+        self.board.set_cell_idx(idx, cell);
+    }
+
+    /// Every move and pass played since the game was created, oldest first. Positions built
+    /// directly, like with [`Game::from_string`], start with an empty history.
+    pub fn move_history(&self) -> &[HistoryEntry] {
+        &self.history
+    }
+
+    /// The number of moves and passes in [`Game::move_history`].
+    pub fn move_count(&self) -> usize {
+        self.history.len()
+    }
+
+    /// The Zobrist hash of the board (see [`Board::compute_hash`]) and the player to move,
+    /// maintained incrementally as moves are played and undone rather than computed from
+    /// scratch. Always equal to [`Game::compute_hash`].
+    pub fn hash(&self) -> u64 {
+        self.hash
+    }
+
+    /// Computes the hash [`Game::hash`] maintains from scratch: the board's hash, with
+    /// [`ZOBRIST_PLAYER_TWO`](zobrist::ZOBRIST_PLAYER_TWO) mixed in when Player Two is to move.
+    pub fn compute_hash(&self) -> u64 {
+        self.board.compute_hash() ^ side_key(self.current_player)
+    }
+
+    /// The player whose turn it is.
+    pub fn current_player(&self) -> Player {
+        self.current_player
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    /// Mutable access to the board, for building positions cell by cell. The returned guard
+    /// derefs to the [`Board`], and brings the Zobrist hash back in line with it when dropped.
+    pub fn board_mut(&mut self) -> BoardMut<'_> {
+        BoardMut { game: self }
+    }
+
+    pub fn play_move(&mut self, m: Move) -> Result<()> {
+        self.play_idx(m.index())
+    }
+
+    /// Plays the move written as a coordinate like `d3` (see [`Move::from_coord`]).
+    pub fn play_coord(&mut self, coord: &str) -> Result<()> {
+        self.play_move(Move::from_coord(coord)?)
+    }
+
     pub fn play_idx(&mut self, index: usize) -> Result<()> {
+        if index >= SIZE {
+            Err(anyhow!("Move out of bounds: {}", index))?;
+        }
+
         let move_set = self.is_valid_move(index % WIDTH, index / WIDTH).ok_or(anyhow!("Invalid move"))?;
 
-        self.board.set_cell_idx(index, Cell::Player(self.current_player));
+        let player = cell_state(Cell::Player(self.current_player));
+        let opponent = cell_state(Cell::Player(self.current_player.opponent()));
+
+        self.hash ^= ZOBRIST_TABLE[index][cell_state(Cell::Empty)] ^ ZOBRIST_TABLE[index][player];
+
+        for idx in &move_set {
+            self.hash ^= ZOBRIST_TABLE[*idx][opponent] ^ ZOBRIST_TABLE[*idx][player];
+        }
+
+        let flip_mask = move_set.iter().fold(0, |mask, idx| mask | 1 << idx);
+        self.board.apply_move_and_flips(index, flip_mask, self.current_player);
+
+        self.history.push(HistoryEntry { move_idx: Some(index), player: self.current_player, flips: flip_mask });
+
+        self.set_current_player(self.current_player.opponent());
+        Ok(())
+    }
+
+    /// Plays `mv` for `player` whether or not it is their turn, leaving `player`'s opponent to
+    /// move, as when setting up a puzzle. Returns an error, leaving the game unchanged, if the
+    /// move is illegal for `player`.
+    pub fn apply_move_for_player(&mut self, mv: usize, player: Player) -> Result<()> {
+        let current_player = self.current_player;
+        self.set_current_player(player);
+
+        self.play_idx(mv).inspect_err(|_| self.set_current_player(current_player))
+    }
+
+    /// Takes back the last move or pass in [`Game::move_history`]: the placed disc is removed,
+    /// the flipped discs go back to the opponent, and it is that player's turn again. This is
+    /// much cheaper than cloning the game before every move to try. Returns an error if there
+    /// is nothing to undo.
+    pub fn undo(&mut self) -> Result<()> {
+        let entry = self.history.pop().ok_or(anyhow!("No moves to undo"))?;
+
+        if let Some(index) = entry.move_idx {
+            let player = cell_state(Cell::Player(entry.player));
+            let opponent = cell_state(Cell::Player(entry.player.opponent()));
+
+            self.hash ^= ZOBRIST_TABLE[index][cell_state(Cell::Empty)] ^ ZOBRIST_TABLE[index][player];
 
-        for idx in move_set {
-            self.board.set_cell_idx(idx, Cell::Player(self.current_player));
+            for idx in iter_bits(entry.flips) {
+                self.hash ^= ZOBRIST_TABLE[idx][opponent] ^ ZOBRIST_TABLE[idx][player];
+            }
+
+            self.board.undo_move_and_flips(index, entry.flips, entry.player);
         }
 
-        self.current_player = self.current_player.opponent();
+        self.set_current_player(entry.player);
         Ok(())
     }
 
+    /// Sets the cell at `idx`, updating `hash` (a [`ZobristHasher`] hash of the board before
+    /// the change) to match the board after it.
+    pub fn update_cell_and_hash(&mut self, idx: usize, cell: Cell, hash: &mut u64, zobrist: &ZobristHasher) {
+        *hash ^= zobrist.key(idx, self.board.get_cell_idx(idx)) ^ zobrist.key(idx, cell);
+        self.set_cell_idx(idx, cell);
+    }
+
     pub fn play(&mut self, x: usize, y: usize) -> Result<()> {
         self.play_idx(at_pos(x, y))
     }
@@ -181,115 +643,1628 @@ impl Game {
     pub fn total_moves(&self) -> usize {
         self.board.total_moves()
     }
-    
-    pub fn from_string(string: &str, player: Player, validate: bool) -> Result<Self> {
-        let mut game = Self::new();
 
-        let mut recorded_possible_moves: Vec<usize> = Vec::new();
+    /// Converts a move index to algebraic notation: a column letter (`a`-`h`) followed by
+    /// a row digit (`1`-`8`), e.g. `d3`.
+    pub fn move_to_san(mv: usize) -> String {
+        Move::from_index(mv).to_coord()
+    }
 
-        let rows = string.split('\n');
+    /// Parses a move written in algebraic notation (see [`Game::move_to_san`]).
+    pub fn san_to_move(san: &str) -> Result<usize> {
+        Ok(Move::from_coord(san)?.index())
+    }
 
-        for (y, row) in rows.enumerate() {
-            if y >= HEIGHT {
-                Err(anyhow!("Too many rows"))?;
-            }
+    /// Names a 0-based ply by its move number and the side playing it, with Black moving
+    /// first: ply `0` is `1. (Black)`, ply `1` is `1. (White)`, ply `2` is `2. (Black)`.
+    pub fn ply_to_notation(ply: usize) -> String {
+        let side = if ply.is_multiple_of(2) { "Black" } else { "White" };
 
-            for (x, character) in row.chars().enumerate() {
-                if x >= WIDTH {
-                    Err(anyhow!("Too many columns"))?;
-                }
+        format!("{}. ({})", ply / 2 + 1, side)
+    }
 
-                let cell = match character {
-                    'X' => Cell::Player(Player::One),
-                    'O' => Cell::Player(Player::Two),
-                    '*' => {
-                        recorded_possible_moves.push(at_pos(x, y));
-                        continue;
-                    }
-                    '-' => Cell::Empty,
-                    _ => Err(anyhow!("Invalid character: {}", character))?,
-                };
+    /// Parses a ply written like [`Game::ply_to_notation`] back to its 0-based count.
+    pub fn notation_to_ply(notation: &str) -> Result<usize> {
+        let (number, side) = notation.trim().split_once(". ")
+            .ok_or_else(|| anyhow!("Invalid ply {:?}, expected a move number and a side like `1. (Black)`", notation))?;
 
-                game.board.set_cell(x, y, cell);
-            }
+        let number = match number.parse::<usize>() {
+            Ok(number) if number > 0 => number,
+            _ => Err(anyhow!("Invalid move number {:?}, expected a number starting from 1", number))?,
+        };
+
+        let offset = match side {
+            "(Black)" => 0,
+            "(White)" => 1,
+            _ => Err(anyhow!("Invalid side {:?}, expected (Black) or (White)", side))?,
+        };
+
+        Ok((number - 1) * 2 + offset)
+    }
+
+    /// The number of discs playing `mv` would flip, or `0` if it isn't a legal move.
+    pub fn count_flips(&self, mv: usize) -> usize {
+        if mv >= SIZE {
+            return 0;
         }
 
-        game.current_player = player;
+        self.is_valid_move(mv % WIDTH, mv / WIDTH).map_or(0, |flips| flips.len())
+    }
 
-        if validate {
-            let mut moves = game.moves();
+    /// Whether `mv` is a corner square.
+    pub fn is_corner_move(mv: usize) -> bool {
+        CORNERS.contains(&mv)
+    }
 
-            moves.sort_unstable();
-            recorded_possible_moves.sort_unstable();
+    /// Describes playing `mv` in a few words, like `flips 4 discs, threatens corner at a1`:
+    /// how many discs it flips, whether it takes a corner, and which corners it opens up for
+    /// the current player or gives to the opponent. Returns an error if `mv` isn't legal.
+    pub fn explain_move(&self, mv: usize) -> Result<String> {
+        let mut after = self.clone();
+        after.play_idx(mv)?;
+
+        let corner_moves = |game: &Game| game.move_indices().into_iter().filter(|mv| Self::is_corner_move(*mv)).collect::<Vec<_>>();
+        let new_corners = |before: &Game, after: &Game| {
+            let before = corner_moves(before);
+            corner_moves(after).into_iter().filter(|corner| !before.contains(corner)).collect::<Vec<_>>()
+        };
 
-            if moves != recorded_possible_moves {
-                Err(anyhow!("real != recorded moves: \n{:?} != {:?}\nparsed game:\n{game}", moves, recorded_possible_moves))?;
-            }
+        let mut opponent = self.clone();
+        opponent.swap_players_unchecked();
+
+        let mut own_after = after.clone();
+        own_after.swap_players_unchecked();
+
+        let threatened = new_corners(self, &own_after);
+        let given = new_corners(&opponent, &after);
+
+        let flips = self.count_flips(mv);
+        let mut parts = vec![format!("flips {} {}", flips, if flips == 1 { "disc" } else { "discs" })];
+
+        if Self::is_corner_move(mv) {
+            parts.push(format!("takes corner {}", Self::move_to_san(mv)));
         }
 
-        Ok(game)
+        let corners = |corners: Vec<usize>| corners.into_iter().map(Self::move_to_san).collect::<Vec<_>>().join(" and ");
+
+        if !threatened.is_empty() {
+            parts.push(format!("threatens corner at {}", corners(threatened)));
+        }
+
+        if !given.is_empty() {
+            parts.push(format!("gives up corner at {}", corners(given)));
+        }
+
+        Ok(parts.join(", "))
     }
 
-    pub fn from_compressed_string(string: &str) {
+    /// Builds a game from the standard starting position, with `player` moving first, by
+    /// playing the set bits of `bitmap` as moves from the least significant bit up. Passes are
+    /// made automatically whenever the player to move has no legal moves.
+    pub fn from_move_bitmap(bitmap: u64, player: Player) -> Result<Self> {
         let mut game = Self::new();
+        game.set_current_player(player);
 
-        let (prefix, game_str) = string.split_at(2);
+        let mut remaining = bitmap;
 
-        game.current_player = match prefix {
-            "X:" => Player::One,
-            "O:" => Player::Two,
-            _ => panic!("Invalid prefix"),
-        };
+        while remaining != 0 {
+            let bit = remaining.trailing_zeros() as usize;
+            remaining &= remaining - 1;
 
-        for (idx, character) in game_str.chars().enumerate() {
-            let x = idx % WIDTH;
-            let y = idx / WIDTH;
+            if game.move_indices().is_empty() {
+                game.pass()?;
+            }
+
+            game.play_idx(bit).map_err(|_| anyhow!("Illegal move at bit {}", bit))?;
+        }
+
+        Ok(game)
+    }
 
-            let cell = match character {
-                'X' => Cell::Player(Player::One),
-                'O' => Cell::Player(Player::Two),
-                '-' => Cell::Empty,
-                _ => panic!("Invalid character: {}", character),
+    /// Replays `moves` from the starting position, with `Some(index)` for a move and `None`
+    /// for a forced pass. The error for an illegal entry names its move number and the
+    /// position it was played in.
+    pub fn from_moves(moves: &[Option<usize>]) -> Result<Self> {
+        let mut game = Self::new();
+
+        for (number, recorded_move) in moves.iter().enumerate() {
+            let (result, description) = match recorded_move {
+                Some(idx) if *idx < SIZE => (game.play_idx(*idx), Self::move_to_san(*idx)),
+                Some(idx) => (game.play_idx(*idx), idx.to_string()),
+                None => (game.pass(), "pass".to_string()),
             };
 
-            game.board.set_cell(x, y, cell);
+            result.map_err(|err| anyhow!("Move {} ({}): {}, in position {}", number + 1, description, err, game.to_string_compact()))?;
         }
+
+        Ok(game)
     }
 
-    pub fn iter(&self) -> std::slice::Iter<Cell> {
-        self.board.iter()
+    /// Encodes the position as [`Board::to_bytes`] followed by the player to move: `0` for
+    /// Player One, `1` for Player Two. The move history is not included.
+    pub fn to_bytes(&self) -> [u8; 17] {
+        let mut bytes = [0; 17];
+        bytes[..16].copy_from_slice(&self.board.to_bytes());
+        bytes[16] = match self.current_player {
+            Player::One => 0,
+            Player::Two => 1,
+        };
+        bytes
     }
-}
 
-impl fmt::Display for Game {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        writeln!(f, "Current player: {}", Cell::Player(self.current_player).to_char())?;
+    /// Decodes a position written by [`Game::to_bytes`]. Returns an error if the bitboards
+    /// overlap or the player byte is not `0` or `1`.
+    pub fn from_bytes(bytes: &[u8; 17]) -> Result<Self> {
+        let board = Board::from_bytes(bytes[..16].try_into().unwrap())?;
 
-        let moves = self.moves();
+        let current_player = match bytes[16] {
+            0 => Player::One,
+            1 => Player::Two,
+            byte => Err(anyhow!("Invalid player byte: {}", byte))?,
+        };
 
-        for y in 0..HEIGHT {
-            for x in 0..WIDTH {
-                let character = if moves.contains(&at_pos(x, y)) {
-                    '*'
-                } else {
-                    self.board.get_cell(x, y).to_char()
-                };
+        Ok(Game::from_board(board, current_player))
+    }
+
+    /// Saves the game as JSON, e.g.
+    /// `{"board":"---…","current_player":"X","start":"X:---…","history":["e3",null]}`.
+    ///
+    /// `board` is the 64 cells, `start` the position (in [`Game::to_string_compact`]'s
+    /// format) the history was played from, and `history` every move in algebraic notation,
+    /// with `null` for a pass, so the game can be replayed from the beginning. Loaded with
+    /// [`Game::from_json`].
+    pub fn to_json(&self) -> String {
+        let mut start = self.clone();
+        while start.undo().is_ok() {}
+
+        let history = self.history.iter()
+            .map(|entry| entry.move_idx.map_or("null".to_string(), |idx| json::quote(&Game::move_to_san(idx))))
+            .collect::<Vec<_>>();
+
+        format!(
+            "{{\"board\":{},\"current_player\":{},\"start\":{},\"history\":[{}]}}",
+            json::quote(&self.to_string_compact()[2..]),
+            json::quote(&self.current_player.to_string()),
+            json::quote(&start.to_string_compact()),
+            history.join(","),
+        )
+    }
+
+    /// Loads a game saved by [`Game::to_json`], replaying its history from `start` (the
+    /// standard starting position if it is missing). Returns an error if the JSON is
+    /// malformed, a field is missing or invalid, a move in the history is illegal, or the
+    /// history doesn't lead to the saved board and player.
+    pub fn from_json(string: &str) -> Result<Self> {
+        let value = json::parse(string)?;
+
+        let field = |name: &str| value.get(name).ok_or(anyhow!("Missing field: {}", name));
+        let string_field = |name: &str| match field(name)? {
+            json::Value::String(string) => Ok(string.as_str()),
+            _ => Err(anyhow!("Expected {} to be a string", name)),
+        };
+
+        let saved = Game::from_compressed_string(&format!("{}:{}", string_field("current_player")?, string_field("board")?))?;
+
+        let mut game = match value.get("start") {
+            Some(_) => Game::from_compressed_string(string_field("start")?)?,
+            None => Game::new(),
+        };
+
+        let json::Value::Array(history) = field("history")? else {
+            Err(anyhow!("Expected history to be an array"))?
+        };
+
+        for (number, recorded_move) in history.iter().enumerate() {
+            let result = match recorded_move {
+                json::Value::String(san) => Game::san_to_move(san).and_then(|idx| game.play_idx(idx)),
+                json::Value::Null => game.pass(),
+                _ => Err(anyhow!("Expected a move or null")),
+            };
+
+            result.map_err(|err| anyhow!("History move {}: {}", number + 1, err))?;
+        }
+
+        if game != saved {
+            Err(anyhow!("The history leads to {}, not the saved position {}", game.to_string_compact(), saved.to_string_compact()))?;
+        }
+
+        Ok(game)
+    }
+
+    /// Reconstructs the position after the first `move_number` entries (moves or passes) of
+    /// `record`, so `0` gives the starting position. Returns an error if `move_number` is past
+    /// the end of the record, or if one of those entries is illegal.
+    pub fn from_game_record_at_move(record: &GameRecord, move_number: usize) -> Result<Self> {
+        if move_number > record.moves.len() {
+            Err(anyhow!("The record has {} moves, requested move {}", record.moves.len(), move_number))?;
+        }
+
+        let positions = GameRecord::new(record.moves[..move_number].to_vec()).replay()?;
+
+        Ok(positions.into_iter().last().expect("replay always includes the starting position"))
+    }
+
+    /// Plays a game from the starting position to the end, asking `player_one` or
+    /// `player_two` for each move, and returns the final position along with every move
+    /// played (`None` for a pass).
+    ///
+    /// Passes are detected by the engine: the players are only asked when they have a legal
+    /// move, and should return one. If a player returns `None` or an illegal move anyway, the
+    /// game stops there.
+    pub fn auto_play_game(player_one: impl Fn(&Game) -> Option<usize>, player_two: impl Fn(&Game) -> Option<usize>) -> (Game, Vec<Option<usize>>) {
+        let mut game = Self::new();
+        let mut history = Vec::new();
+
+        while !game.is_game_over() {
+            if game.move_indices().is_empty() {
+                game.pass().expect("passing is legal without any moves");
+                history.push(None);
+                continue;
+            }
+
+            let chosen_move = match game.current_player {
+                Player::One => player_one(&game),
+                Player::Two => player_two(&game),
+            };
+
+            match chosen_move {
+                Some(idx) if game.play_idx(idx).is_ok() => history.push(Some(idx)),
+                _ => break,
+            }
+        }
+
+        (game, history)
+    }
+
+    /// Plays `n` uniformly random moves from the starting position, passing when forced.
+    /// Returns an error if `n` is more than the 60 moves a game can last, or if the game
+    /// ends before `n` moves are played.
+    pub fn random_position_at_move_n(n: usize, rng: &mut fastrand::Rng) -> Result<Self> {
+        if n > SIZE - 4 {
+            Err(anyhow!("A game lasts at most {} moves, requested {}", SIZE - 4, n))?;
+        }
+
+        let mut game = Self::new();
+
+        for played in 0..n {
+            if game.move_indices().is_empty() {
+                game.pass()?;
+            }
+
+            let moves = game.move_indices();
+
+            if moves.is_empty() {
+                Err(anyhow!("Game ended after {} moves", played))?;
+            }
+
+            game.play_idx(moves[rng.usize(..moves.len())])?;
+        }
+
+        Ok(game)
+    }
+
+    /// The number of moves played so far, counted from the 4 starting discs. It is `0` at the
+    /// start, and otherwise the 1-based number of the last move played. Passes don't count.
+    pub fn move_number(&self) -> usize {
+        self.total_moves().saturating_sub(4)
+    }
+
+    /// The current disc counts, like `X: 30 | O: 34`.
+    pub fn score_display(&self) -> String {
+        let (player_one, player_two) = self.score();
+
+        format!("X: {} | O: {}", player_one, player_two)
+    }
+
+    /// A one line summary for display, like `Move 26 | X: 14 | O: 16 | X to move`.
+    pub fn to_header_string(&self) -> String {
+        format!("Move {} | {} | {} to move", self.move_number(), self.score_display(), self.current_player)
+    }
+
+    /// Plays a random legal move, chosen with probability proportional to its entry in
+    /// `weights` (negative weights count as `0`), and returns it. If every legal move has a
+    /// weight of `0`, one is chosen uniformly instead. Returns `None`, without changing the
+    /// game, if there are no legal moves.
+    pub fn apply_random_move_weighted(&mut self, weights: &[f32; SIZE], rng: &mut fastrand::Rng) -> Option<usize> {
+        let moves = self.move_indices();
+
+        if moves.is_empty() {
+            return None;
+        }
+
+        let move_weights = moves.iter().map(|idx| weights[*idx].max(0.0)).collect::<Vec<_>>();
+        let total: f32 = move_weights.iter().sum();
+
+        let chosen = if total > 0.0 {
+            let mut target = rng.f32() * total;
+            let mut chosen = moves[0];
+
+            // rounding can leave `target` just past the end, so fall back to the last weighted move
+            for (idx, weight) in moves.iter().zip(&move_weights).filter(|(_, weight)| **weight > 0.0) {
+                chosen = *idx;
+
+                if target < *weight {
+                    break;
+                }
+
+                target -= weight;
+            }
+
+            chosen
+        } else {
+            moves[rng.usize(..moves.len())]
+        };
+
+        self.play_idx(chosen).unwrap();
+
+        Some(chosen)
+    }
+
+    pub fn board_is_full(&self) -> bool {
+        self.board.is_full()
+    }
+
+    pub fn num_empty_squares(&self) -> usize {
+        SIZE - self.total_moves()
+    }
+
+    /// Solves the position exactly, returning the final disc difference for the current
+    /// player under perfect play (with no heuristics involved). The search grows
+    /// exponentially with the number of empty squares, so positions with more than
+    /// `max_empties` of them are refused with [`ReversiError::TooManyEmpties`].
+    pub fn endgame_search_with_empties(&self, max_empties: usize) -> Result<isize> {
+        let actual = self.num_empty_squares();
+
+        if actual > max_empties {
+            Err(ReversiError::TooManyEmpties { actual, max: max_empties })?;
+        }
+
+        let mut game = solve::search_game(self);
+        let score = solve::alpha_beta(&mut game, -(SIZE as isize), SIZE as isize);
+        solve::release_search_game(game);
+
+        score
+    }
+
+    /// Checks whether a sequence of moves can be played from the current state, without
+    /// modifying it. Passes are detected automatically: if the player to move has no legal
+    /// moves, the turn goes to the opponent before the next move is played.
+    pub fn is_valid_sequence(&self, moves: &[usize]) -> bool {
+        let mut game = self.clone();
+
+        for possible_move in moves {
+            if game.move_indices().is_empty() && game.pass().is_err() {
+                return false;
+            }
+
+            if game.play_idx(*possible_move).is_err() {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Estimates how many more moves will be played before the game ends.
+    ///
+    /// The estimate is `empty_squares * (1 - pass_rate)`, where `pass_rate` is derived from
+    /// the mobility ratio `r = min(mobility) / max(mobility)` of the two players as
+    /// `(1 - r) / 2`: balanced mobility suggests both sides will keep playing, while a side
+    /// with no moves at all is expected to pass every other turn. If neither side can move,
+    /// the game is over and the estimate is `0`.
+    ///
+    /// This is only a rough heuristic - it looks at the current position alone, so it can't
+    /// foresee passes deep in the endgame, and it never accounts for regions of the board
+    /// that will remain empty. Treat it as an upper-biased guess, not a bound.
+    pub fn expected_remaining_moves(&self) -> f64 {
+        let empty_squares = (SIZE - self.total_moves()) as f64;
+
+        let own_mobility = self.move_indices().len();
+
+        let mut opponent = self.clone();
+        opponent.swap_players_unchecked();
+        let opponent_mobility = opponent.move_indices().len();
+
+        let max_mobility = own_mobility.max(opponent_mobility);
+
+        if max_mobility == 0 {
+            return 0.0;
+        }
+
+        let mobility_ratio = own_mobility.min(opponent_mobility) as f64 / max_mobility as f64;
+        let pass_rate = (1.0 - mobility_ratio) / 2.0;
+
+        empty_squares * (1.0 - pass_rate)
+    }
+
+    pub fn from_string(string: &str, player: Player, validate: bool) -> Result<Self> {
+        let mut game = Self::new();
+
+        let mut recorded_possible_moves: Vec<usize> = Vec::new();
+
+        let rows = string.split('\n');
+
+        for (y, row) in rows.enumerate() {
+            if y >= HEIGHT {
+                Err(anyhow!("Too many rows"))?;
+            }
+
+            for (x, character) in row.chars().enumerate() {
+                if x >= WIDTH {
+                    Err(anyhow!("Too many columns"))?;
+                }
+
+                if character == '*' {
+                    recorded_possible_moves.push(at_pos(x, y));
+                }
+
+                game.set_cell_idx(at_pos(x, y), Cell::try_from(character)?);
+            }
+        }
+
+        game.set_current_player(player);
+
+        if validate {
+            let mut moves = game.move_indices();
+
+            moves.sort_unstable();
+            recorded_possible_moves.sort_unstable();
+
+            if moves != recorded_possible_moves {
+                Err(anyhow!("real != recorded moves: \n{:?} != {:?}\nparsed game:\n{game}", moves, recorded_possible_moves))?;
+            }
+        }
+
+        Ok(game)
+    }
+
+    pub fn from_compressed_string(string: &str) -> Result<Self> {
+        let mut game = Self::new();
+
+        let (prefix, game_str) = string.split_at_checked(2).ok_or(anyhow!("Missing player prefix"))?;
+
+        let player = prefix.strip_suffix(':').and_then(|player| player.chars().next()).ok_or(anyhow!("Invalid prefix: {}", prefix))?;
+        game.set_current_player(Player::try_from(player)?);
+
+        if game_str.chars().count() != SIZE {
+            Err(anyhow!("Expected {} cells, found {}", SIZE, game_str.chars().count()))?;
+        }
+
+        for (idx, character) in game_str.chars().enumerate() {
+            let x = idx % WIDTH;
+            let y = idx / WIDTH;
+
+            game.set_cell_idx(at_pos(x, y), Cell::try_from(character)?);
+        }
+
+        Ok(game)
+    }
+
+    /// Encodes the position as 129 characters: the player one bitmask as 64 `0`s and `1`s,
+    /// the player two bitmask the same way, then `X` or `O` for the player to move. Character
+    /// `i` of each mask is cell `i`.
+    pub fn encode_as_bitstring(&self) -> String {
+        let bits = |mask: u64| (0..SIZE).map(move |idx| if mask & 1 << idx != 0 { '1' } else { '0' });
+
+        bits(self.board.player_mask(Player::One))
+            .chain(bits(self.board.player_mask(Player::Two)))
+            .chain(std::iter::once(self.current_player.to_char()))
+            .collect()
+    }
+
+    /// Parses a position written by [`Game::encode_as_bitstring`].
+    pub fn decode_from_bitstring(string: &str) -> Result<Game> {
+        let characters = string.chars().collect::<Vec<_>>();
+
+        if characters.len() != 2 * SIZE + 1 {
+            Err(anyhow!("Expected {} characters, found {}", 2 * SIZE + 1, characters.len()))?;
+        }
+
+        let mask = |bits: &[char]| {
+            bits.iter().enumerate().try_fold(0u64, |mask, (idx, bit)| match bit {
+                '0' => Ok(mask),
+                '1' => Ok(mask | 1 << idx),
+                _ => Err(anyhow!("Invalid bit: {}", bit)),
+            })
+        };
+
+        let board = Board::from_masks(mask(&characters[..SIZE])?, mask(&characters[SIZE..2 * SIZE])?)?;
+
+        Ok(Game::from_board(board, Player::try_from(characters[2 * SIZE])?))
+    }
+
+    /// Packs the position into a `u128`, to compare or hash positions cheaply. With player one
+    /// to move, bits 0-63 are player one's discs and bits 64-127 are player two's (bit `i` of
+    /// each half is cell `i`). The discs of a real position never overlap, which leaves the
+    /// codes with overlapping halves free to mark player two to move:
+    ///
+    /// - if any square is empty, both halves are inverted, so they overlap exactly on the empty
+    ///   squares (and together cover every square)
+    /// - a full board has no empty square to overlap on, so bit 0 of both halves is set and bit
+    ///   1 cleared instead, with player one's discs in the remaining 124 bits
+    pub fn serialize_as_position_code(&self) -> u128 {
+        let player_one = self.board.player_mask(Player::One);
+        let player_two = self.board.player_mask(Player::Two);
+
+        let (low, high) = match self.current_player {
+            Player::One => (player_one, player_two),
+            Player::Two if !self.board.is_full() => (!player_one, !player_two),
+            Player::Two => (1 | player_one << 2, 1 | (player_one >> 62) << 2),
+        };
+
+        low as u128 | (high as u128) << 64
+    }
+
+    /// Unpacks a position packed by [`Game::serialize_as_position_code`].
+    pub fn from_position_code(code: u128) -> Result<Game> {
+        let (low, high) = (code as u64, (code >> 64) as u64);
+
+        let (player_one, player_two, current_player) = if low & high == 0 {
+            (low, high, Player::One)
+        } else if low | high == u64::MAX {
+            (!low, !high, Player::Two)
+        } else if low & 0b11 == 1 && high & 0b11 == 1 && high >> 4 == 0 {
+            let player_one = low >> 2 | (high >> 2) << 62;
+            (player_one, !player_one, Player::Two)
+        } else {
+            Err(anyhow!("Invalid position code: {:#034x}", code))?
+        };
+
+        Ok(Game::from_board(Board::from_masks(player_one, player_two)?, current_player))
+    }
+
+    /// Parses a FEN-like position: rows from top to bottom separated by `/`, where `X` and `O`
+    /// are discs and digits are runs of empty squares, followed by the player to move.
+    ///
+    /// The starting position is `8/8/8/3XO3/3OX3/8/8/8 X`.
+    pub fn from_fen(string: &str) -> Result<Self> {
+        let mut game = Self::new();
+
+        let (rows, player) = string.trim().split_once(' ').ok_or(anyhow!("Missing player to move"))?;
+
+        let mut player_chars = player.trim().chars();
+
+        game.set_current_player(match (player_chars.next(), player_chars.next()) {
+            (Some(character), None) => Player::try_from(character)?,
+            _ => Err(anyhow!("Invalid player: {}", player))?,
+        });
+
+        let rows = rows.split('/').collect::<Vec<_>>();
+
+        if rows.len() != HEIGHT {
+            Err(anyhow!("Expected {} rows, found {}", HEIGHT, rows.len()))?;
+        }
+
+        for (y, row) in rows.into_iter().enumerate() {
+            let mut x = 0;
+
+            for character in row.chars() {
+                // digits are runs of empty squares, so only discs are left for `Player`
+                let (cell, count) = match character {
+                    '1'..='8' => (Cell::Empty, character.to_digit(10).unwrap() as usize),
+                    _ => (Cell::Player(Player::try_from(character)?), 1),
+                };
+
+                if x + count > WIDTH {
+                    Err(anyhow!("Too many columns in row {}", y + 1))?;
+                }
+
+                for _ in 0..count {
+                    game.set_cell_idx(at_pos(x, y), cell);
+                    x += 1;
+                }
+            }
+
+            if x != WIDTH {
+                Err(anyhow!("Too few columns in row {}", y + 1))?;
+            }
+        }
+
+        Ok(game)
+    }
+
+    /// Parses the main line of an Othello SGF record (`GM[2]`), replaying its `B[..]` and
+    /// `W[..]` moves from the standard starting position. Moves are written as a column
+    /// letter and a row digit, like `d3`. Passes may be recorded as an empty value or `PA`,
+    /// and are also inferred when a player has no legal moves.
+    pub fn from_sgf(string: &str) -> Result<Self> {
+        let mut game = Self::new();
+
+        for node in string.split(';').skip(1) {
+            let node = node.trim_start();
+
+            let player = if node.starts_with("B[") {
+                Player::One
+            } else if node.starts_with("W[") {
+                Player::Two
+            } else {
+                continue;
+            };
+
+            let value = &node[2..node.find(']').ok_or(anyhow!("Unterminated move: {}", node))?];
+
+            if game.current_player != player {
+                game.pass().map_err(|_| anyhow!("Move played out of turn: {}", value))?;
+            }
+
+            if value.is_empty() || value.eq_ignore_ascii_case("pa") {
+                game.pass()?;
+                continue;
+            }
+
+            game.play_idx(Self::san_to_move(value)?)?;
+        }
+
+        Ok(game)
+    }
+
+    /// Formats `moves`, played from this position, as a PGN game: the tags of `metadata`
+    /// followed by the numbered moves in algebraic notation, with `--` for passes, and the
+    /// result. Player one plays black. The result is `*` unless the moves end the game.
+    pub fn to_pgn_string(&self, moves: &[Option<usize>], metadata: &GameMetadata) -> String {
+        let mut game = self.clone();
+        let mut legal = true;
+
+        for recorded_move in moves {
+            legal &= match recorded_move {
+                Some(idx) => game.play_idx(*idx).is_ok(),
+                None => game.pass().is_ok(),
+            };
+        }
+
+        let result = match game.result().filter(|_| legal) {
+            Some(GameResult::Win(Player::One)) => "1-0",
+            Some(GameResult::Win(Player::Two)) => "0-1",
+            Some(GameResult::Draw) => "1/2-1/2",
+            None => "*",
+        };
+
+        let tags = [
+            ("Event", metadata.event.as_deref().unwrap_or("?")),
+            ("Date", metadata.date.as_deref().unwrap_or("????.??.??")),
+            ("Black", &metadata.black_player),
+            ("White", &metadata.white_player),
+            ("Result", result),
+        ];
+
+        let mut pgn = tags.iter().map(|(tag, value)| format!("[{} \"{}\"]\n", tag, value)).collect::<String>();
+        pgn.push('\n');
+
+        for (ply, recorded_move) in moves.iter().enumerate() {
+            if ply % 2 == 0 {
+                pgn.push_str(&format!("{}. ", ply / 2 + 1));
+            }
+
+            match recorded_move {
+                Some(idx) => pgn.push_str(&Self::move_to_san(*idx)),
+                None => pgn.push_str("--"),
+            }
+
+            pgn.push(' ');
+        }
+
+        pgn.push_str(result);
+        pgn.push('\n');
+
+        pgn
+    }
+
+    /// Parses a PGN game written by [`Game::to_pgn_string`] (or a similar tool), replaying its
+    /// moves from the standard starting position. Returns the final position, the moves with
+    /// `None` for passes, and the metadata from the tags. Passes may be written as `--` or
+    /// `pass`, or left out when a player has no legal moves. Comments in braces are skipped.
+    pub fn from_pgn_string(string: &str) -> Result<(Self, Vec<Option<usize>>, GameMetadata)> {
+        let mut metadata = GameMetadata::default();
+        let mut movetext = String::new();
+
+        for line in string.lines().map(str::trim) {
+            let Some(tag) = line.strip_prefix('[') else {
+                movetext.push_str(line);
+                movetext.push(' ');
+                continue;
+            };
+
+            let (name, value) = tag.strip_suffix(']').and_then(|tag| tag.split_once(' ')).ok_or(anyhow!("Invalid tag: {}", line))?;
+            let value = value.trim().trim_matches('"').to_string();
+            let known = !value.chars().all(|character| character == '?' || character == '.');
+
+            match name {
+                "Black" => metadata.black_player = value,
+                "White" => metadata.white_player = value,
+                "Date" if known => metadata.date = Some(value),
+                "Event" if known => metadata.event = Some(value),
+                _ => {}
+            }
+        }
+
+        let mut game = Self::new();
+        let mut moves = Vec::new();
+        let mut in_comment = false;
+
+        for token in movetext.split_whitespace() {
+            if in_comment || token.starts_with('{') {
+                in_comment = !token.ends_with('}');
+                continue;
+            }
+
+            if matches!(token, "*" | "1-0" | "0-1" | "1/2-1/2") {
+                continue;
+            }
+
+            // move numbers may be attached to the move that follows them, like `1.f5`
+            let token = token.trim_start_matches(|character: char| character.is_ascii_digit() || character == '.');
+
+            if token.is_empty() {
+                continue;
+            }
+
+            if token == "--" || token.eq_ignore_ascii_case("pass") {
+                game.pass().map_err(|err| anyhow!("Move {}: {}", moves.len() + 1, err))?;
+                moves.push(None);
+                continue;
+            }
+
+            let idx = Self::san_to_move(&token.to_ascii_lowercase())?;
+
+            if game.legal_moves_mask() == 0 {
+                game.pass()?;
+                moves.push(None);
+            }
+
+            game.play_idx(idx).map_err(|err| anyhow!("Move {}: {}", moves.len() + 1, err))?;
+            moves.push(Some(idx));
+        }
+
+        Ok((game, moves, metadata))
+    }
+
+    /// Iterates over all 64 cells in row-major order, without allocating.
+    pub fn iter(&self) -> impl Iterator<Item = Cell> + '_ {
+        self.board.iter().copied()
+    }
+
+    /// Like [`Game::iter`], along with each cell's `(x, y)` position.
+    pub fn iter_with_pos(&self) -> impl Iterator<Item = (usize, usize, Cell)> + '_ {
+        self.iter().enumerate().map(|(idx, cell)| (idx % WIDTH, idx / WIDTH, cell))
+    }
+
+    /// The compressed format parsed by [`Game::from_compressed_string`], e.g. `X:` followed by
+    /// all 64 cells.
+    pub fn to_string_compact(&self) -> String {
+        format!("{self:?}")
+    }
+
+    /// Renders every position up to `depth` plies deep as an indented tree, one node per
+    /// line. Each node shows the move that led to it, its [`Game::to_string_compact`] string
+    /// and its static evaluation for the player to move. Forced passes are shown as `pass`.
+    ///
+    /// The tree grows exponentially with `depth`, so keep it small.
+    pub fn to_move_tree_string(&self, depth: usize) -> String {
+        fn render(game: &Game, mv: Option<&str>, level: usize, depth: usize, output: &mut String) {
+            let prefix = match mv {
+                Some(mv) => format!("{}|-- {} ", "    ".repeat(level - 1), mv),
+                None => String::new(),
+            };
+
+            output.push_str(&format!("{}{} {}\n", prefix, game.to_string_compact(), game.evaluate()));
+
+            if level == depth {
+                return;
+            }
+
+            let moves = game.move_indices();
+
+            if moves.is_empty() {
+                let mut new_game = game.clone();
+                new_game.pass().expect("passing is legal without any moves");
+
+                if !new_game.move_indices().is_empty() {
+                    render(&new_game, Some("pass"), level + 1, depth, output);
+                }
+            }
+
+            for possible_move in moves {
+                let mut new_game = game.clone();
+                new_game.play_idx(possible_move).unwrap();
+
+                render(&new_game, Some(&Game::move_to_san(possible_move)), level + 1, depth, output);
+            }
+        }
+
+        let mut output = String::new();
+        render(self, None, 0, depth, &mut output);
+
+        output
+    }
+
+    /// Formats the board with solver scores overlaid on the cells they belong to.
+    ///
+    /// `scores` maps move indices to their scores. Scored cells show the score, while the
+    /// rest show their disc character (or `*` for an unscored legal move). With scores
+    /// present every cell is right-justified in a 3-character field; with an empty map the
+    /// output is identical to the `Display` implementation.
+    pub fn fmt_score_overlay(&self, scores: &HashMap<usize, isize>, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Current player: {}", self.current_player)?;
+
+        let moves = self.move_indices();
+        let width = if scores.is_empty() { 1 } else { 3 };
+
+        for (x, y, cell) in self.iter_with_pos() {
+            let idx = at_pos(x, y);
+
+            if let Some(score) = scores.get(&idx) {
+                write!(f, "{:>width$}", score)?;
+            } else if moves.contains(&idx) {
+                write!(f, "{:>width$}", '*')?;
+            } else {
+                write!(f, "{:>width$}", cell)?;
+            }
+
+            if x == WIDTH - 1 {
+                writeln!(f)?;
+            }
+        }
 
-                write!(f, "{}", character)?;
+        Ok(())
+    }
+
+    /// Convenience wrapper around [`Game::fmt_score_overlay`] returning a `String`.
+    pub fn score_overlay_string(&self, scores: &HashMap<usize, isize>) -> String {
+        struct ScoreOverlay<'a>(&'a Game, &'a HashMap<usize, isize>);
+
+        impl fmt::Display for ScoreOverlay<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt_score_overlay(self.1, f)
             }
-            writeln!(f)?;
         }
 
-        Ok(())
+        ScoreOverlay(self, scores).to_string()
+    }
+
+    /// Formats the board with the principal variation `pv` numbered on it: the first move's
+    /// cell shows `1`, the second's `2` and so on. Numbers are right-aligned to the width of
+    /// the longest one.
+    pub fn to_pv_board_string(&self, pv: &[usize]) -> String {
+        let width = pv.len().to_string().len();
+        let mut string = String::new();
+
+        for y in 0..HEIGHT {
+            for x in 0..WIDTH {
+                let idx = at_pos(x, y);
+
+                match pv.iter().rposition(|mv| *mv == idx) {
+                    Some(ply) => string.push_str(&format!("{:>width$}", ply + 1)),
+                    None => string.push_str(&format!("{:>width$}", self.board.get_cell(x, y))),
+                }
+            }
+            string.push('\n');
+        }
+
+        string
+    }
+
+    /// Draws the board as a TikZ picture for LaTeX documents: Player One's discs are filled
+    /// circles, Player Two's are empty circles and the current player's legal moves are
+    /// crosses. Columns are labelled `a`-`h` and rows `1`-`8`, with row 1 at the top like
+    /// [`Game`]'s `Display`. Needs `\usepackage{tikz}` in the preamble.
+    pub fn to_latex_board(&self) -> String {
+        let moves = self.legal_moves_mask();
+        let centre = |x: usize, y: usize| format!("({}.5,{}.5)", x, HEIGHT - 1 - y);
+
+        let mut latex = String::from("% Requires \\usepackage{tikz}\n\\begin{tikzpicture}[scale=0.5]\n");
+        latex.push_str(&format!("  \\draw (0,0) grid ({},{});\n", WIDTH, HEIGHT));
+
+        for x in 0..WIDTH {
+            latex.push_str(&format!("  \\node at ({}.5,{}.5) {{{}}};\n", x, HEIGHT, (b'a' + x as u8) as char));
+        }
+
+        for y in 0..HEIGHT {
+            latex.push_str(&format!("  \\node at (-0.5,{}.5) {{{}}};\n", HEIGHT - 1 - y, y + 1));
+        }
+
+        for (x, y, cell) in self.iter_with_pos() {
+            match cell {
+                Cell::Player(Player::One) => latex.push_str(&format!("  \\fill {} circle (0.4);\n", centre(x, y))),
+                Cell::Player(Player::Two) => latex.push_str(&format!("  \\draw {} circle (0.4);\n", centre(x, y))),
+                Cell::Empty if moves >> at_pos(x, y) & 1 == 1 => latex.push_str(&format!("  \\node at {} {{$\\times$}};\n", centre(x, y))),
+                Cell::Empty => {}
+            }
+        }
+
+        latex.push_str("\\end{tikzpicture}\n");
+        latex
+    }
+
+    /// Searches this position to the end of the game within the window `(alpha, beta)`,
+    /// returning the score and the moves whose exact score fell inside the window, best
+    /// first.
+    ///
+    /// Every root move is searched with the whole window, so each returned move has an exact
+    /// score. If the position's score falls outside the window (a non-PV node), the score is
+    /// only a bound and the moves are empty. Both ends of the window are exclusive, so a
+    /// wipe-out scoring `±64` is only exact with a window of `(-65, 65)`.
+    ///
+    /// Scores are the final disc difference for the player to move, as with
+    /// [`Game::endgame_search_with_empties`], not the scale of [`solve::negamax`], which
+    /// scores a game-ending win by how early it comes.
+    pub fn alpha_beta_window(&self, alpha: isize, beta: isize) -> (isize, Vec<usize>) {
+        let mut scores = Vec::new();
+        let mut game = solve::search_game(self);
+
+        let best_score = if self.move_indices().is_empty() {
+            solve::alpha_beta(&mut game, alpha, beta).unwrap()
+        } else {
+            let mut best_score = -(SIZE as isize);
+
+            for possible_move in self.move_indices() {
+                game.play_idx(possible_move).unwrap();
+                let score = -solve::alpha_beta(&mut game, -beta, -alpha).unwrap();
+                game.undo().unwrap();
+
+                best_score = best_score.max(score);
+
+                if score >= beta {
+                    break;
+                }
+
+                if score > alpha {
+                    scores.push((score, possible_move));
+                }
+            }
+
+            best_score
+        };
+
+        solve::release_search_game(game);
+
+        if best_score <= alpha || best_score >= beta {
+            return (best_score, Vec::new());
+        }
+
+        scores.sort_by_key(|(score, _)| -score);
+
+        (best_score, scores.into_iter().map(|(_, possible_move)| possible_move).collect())
+    }
+
+    /// Visits every position reachable from this one within `max_depth` plies, calling
+    /// `callback` with each position and its depth (the root is depth `0`). Positions are
+    /// visited in depth-first order, and transpositions are visited once per path.
+    ///
+    /// A forced pass counts as a ply; finished games have no children.
+    pub fn depth_first_search(&self, max_depth: usize, mut callback: impl FnMut(&Game, usize)) {
+        fn visit(game: &Game, depth: usize, max_depth: usize, callback: &mut dyn FnMut(&Game, usize)) {
+            callback(game, depth);
+
+            if depth == max_depth {
+                return;
+            }
+
+            let moves = game.move_indices();
+
+            if moves.is_empty() {
+                let mut new_game = game.clone();
+                new_game.pass().expect("passing is legal without any moves");
+
+                if !new_game.move_indices().is_empty() {
+                    visit(&new_game, depth + 1, max_depth, callback);
+                }
+
+                return;
+            }
+
+            for possible_move in moves {
+                let mut new_game = game.clone();
+                new_game.play_idx(possible_move).unwrap();
+
+                visit(&new_game, depth + 1, max_depth, callback);
+            }
+        }
+
+        visit(self, 0, max_depth, &mut callback);
+    }
+
+    /// Counts the positions exactly `depth` plies away, with forced passes counting as a ply.
+    pub fn perft(&self, depth: usize) -> u64 {
+        let mut count = 0;
+
+        self.depth_first_search(depth, |_, node_depth| {
+            if node_depth == depth {
+                count += 1;
+            }
+        });
+
+        count
+    }
+
+    /// Whether the position is close enough to the end of the game (at most
+    /// [`solve::ENDGAME_THRESHOLD`] empty squares) for the exact solver to handle it.
+    pub fn is_theoretically_solved(&self) -> bool {
+        SIZE - self.total_moves() <= solve::ENDGAME_THRESHOLD
+    }
+
+    /// Suggests a solving strategy for the current phase of the game. Midgame searches get
+    /// deeper as the board fills up and the branching factor drops.
+    pub fn suggested_solver(&self) -> solve::SolverStrategy {
+        let empty_squares = SIZE - self.total_moves();
+
+        if self.is_theoretically_solved() {
+            solve::SolverStrategy::PerfectEndgame
+        } else if empty_squares > solve::OPENING_THRESHOLD {
+            solve::SolverStrategy::RandomPlayout
+        } else if empty_squares > 30 {
+            solve::SolverStrategy::HeuristicSearch(4)
+        } else {
+            solve::SolverStrategy::HeuristicSearch(6)
+        }
+    }
+
+    /// Scores the moves of this position, preferring the opening book.
+    ///
+    /// If the position (or a symmetric one) is in `book`, its book moves are returned with
+    /// [`BOOK_SCORE`] added to their scores to mark them. Otherwise the position is searched
+    /// with [`solve::solve_at_depth`].
+    pub fn solve_with_book(&self, book: &OpeningBook, depth: usize) -> Vec<(isize, usize)> {
+        match book.get(self) {
+            Some(moves) => moves.into_iter().map(|(score, mv)| (score + BOOK_SCORE, mv)).collect(),
+            None => solve::solve_at_depth(self, depth),
+        }
+    }
+
+    /// The expected line of best play for `depth` plies, as `(move, score)` pairs. Even
+    /// indices are the current player's moves and odd indices the opponent's replies; each
+    /// score is the depth-limited negamax score of that move for the player making it, searched
+    /// to the end of the line.
+    ///
+    /// The line ends early if the game ends or a player has to pass.
+    pub fn get_best_continuation(&self, depth: usize) -> Vec<(usize, isize)> {
+        let mut game = self.clone();
+        let mut continuation = Vec::with_capacity(depth);
+
+        for ply in 0..depth {
+            let Some((score, best_move)) = game.best_scored_move(depth - ply - 1) else {
+                break;
+            };
+
+            game.play_idx(best_move).unwrap();
+            continuation.push((best_move, score));
+        }
+
+        continuation
+    }
+
+    /// The first of the highest scoring moves by [`solve::solve_at_depth`], with its score.
+    fn best_scored_move(&self, depth: usize) -> Option<(isize, usize)> {
+        solve::solve_at_depth(self, depth).into_iter().reduce(|best, next| if next.0 > best.0 { next } else { best })
+    }
+
+    /// The current player's best reply, searched `depth` plies past it, if the opponent were
+    /// to answer `opponent_move` from this position, i.e. "if they play this, I play that".
+    /// `opponent_move` is played for the player to move here. Returns `None` if the reply
+    /// would have to be a pass (or the game would be over), and an error if `opponent_move`
+    /// is illegal.
+    pub fn best_response(&self, opponent_move: usize, depth: usize) -> Result<Option<usize>> {
+        let mut game = self.clone();
+        game.play_idx(opponent_move)?;
+
+        Ok(game.best_scored_move(depth).map(|(_, best_move)| best_move))
+    }
+
+    /// [`solve::solve`]s every position, in parallel across the available cores. Results are
+    /// in the same order as `positions`.
+    pub fn bulk_solve(positions: &[Game]) -> Vec<Vec<(isize, Move)>> {
+        par_map(positions, |game| solve::solve(game, None))
+    }
+
+    /// [`solve::solve`]s every position to `depth`, in parallel across the available cores.
+    /// Results are in the same order as `positions`, with moves as [`Move`]s like
+    /// [`Game::bulk_solve`].
+    pub fn bulk_solve_at_depth(positions: &[Game], depth: usize) -> Vec<Vec<(isize, Move)>> {
+        par_map(positions, |game| solve::solve(game, Some(depth)))
+    }
+
+    /// Returns up to `k` legal moves with the highest depth-limited negamax scores, best
+    /// first. Ties keep move generation order.
+    pub fn top_k_moves(&self, k: usize, depth: usize) -> Vec<usize> {
+        let mut scores = solve::solve_at_depth(self, depth);
+
+        scores.sort_by_key(|(score, _)| -score);
+
+        scores.into_iter().take(k).map(|(_, possible_move)| possible_move).collect()
+    }
+
+    /// The best move by its exact final disc difference for the current player, or `None` if
+    /// there are no legal moves. The first move reaching the upper bound of
+    /// [`Game::score_bounds`], the best score the position allows, is returned straight away
+    /// without searching the rest; otherwise the first of the best scoring moves is.
+    pub fn solve_first_move(&self) -> Option<usize> {
+        let max_score = self.score_bounds().1;
+        let mut tt = solve::TranspositionTable::new();
+        let mut new_game = solve::search_game(self);
+        let mut best: Option<(isize, usize)> = None;
+
+        for possible_move in self.move_indices() {
+            new_game.play_idx(possible_move).unwrap();
+            let score = -solve::endgame_score(&mut new_game, &mut tt).unwrap();
+            new_game.undo().unwrap();
+
+            if best.is_none_or(|(best_score, _)| score > best_score) {
+                best = Some((score, possible_move));
+            }
+
+            if score >= max_score {
+                break;
+            }
+        }
+
+        solve::release_search_game(new_game);
+
+        best.map(|(_, possible_move)| possible_move)
+    }
+
+    /// Whether the current player is ahead by [`Game::evaluate`], but every legal move hands
+    /// the advantage to the opponent: one ply of [`solve::negamax_depth`] leaves the opponent
+    /// with a better score than theirs after each of them.
+    ///
+    /// This is a heuristic. It trusts the static evaluation, which can be wrong about how bad
+    /// a move really is, so it is not a proof of zugzwang. A forced pass is not counted.
+    pub fn is_zugzwang(&self) -> bool {
+        let moves = self.move_indices();
+
+        if moves.is_empty() || self.evaluate() <= 0 {
+            return false;
+        }
+
+        moves.into_iter().all(|possible_move| {
+            let mut new_game = self.clone();
+            new_game.play_idx(possible_move).unwrap();
+
+            solve::negamax_depth(&new_game, 0, -(SIZE as isize), SIZE as isize, &Game::evaluate).unwrap() > 0
+        })
+    }
+
+    /// Static evaluation of the position for the current player, using the default
+    /// [`EvalWeights`].
+    pub fn evaluate(&self) -> isize {
+        self.eval_with_weights(&EvalWeights::default())
+    }
+
+    /// A fast evaluation of immediate threats only, for the current player, meant to seed move
+    /// ordering before the full [`Game::evaluate`]. It looks one move ahead and no further.
+    ///
+    /// Each move that ends the game in the current player's favour is worth a lot; each such
+    /// move the opponent would have if it were their turn (a threat that needs blocking) costs
+    /// half as much. Discs on X-squares next to an empty corner, which hand that corner to the
+    /// other side, cost a little for the current player and gain a little for the opponent's.
+    pub fn tactical_score(&self) -> isize {
+        const WINNING_MOVE: isize = 100;
+        const THREAT: isize = 50;
+        const X_SQUARE_EXPOSURE: isize = 10;
+
+        let mut opponent = self.clone();
+        opponent.swap_players_unchecked();
+
+        let exposed_x_squares = |player: Player| {
+            (0..SIZE)
+                .filter(|idx| CellType::of(*idx) == CellType::XSquare && self.board.get_cell_idx(*idx) == Cell::Player(player))
+                .filter(|idx| {
+                    let (x, y) = (idx % WIDTH, idx / WIDTH);
+                    let corner = at_pos(if x < WIDTH / 2 { 0 } else { WIDTH - 1 }, if y < HEIGHT / 2 { 0 } else { HEIGHT - 1 });
+
+                    self.board.get_cell_idx(corner) == Cell::Empty
+                })
+                .count() as isize
+        };
+
+        WINNING_MOVE * self.immediate_wins() as isize
+            - THREAT * opponent.immediate_wins() as isize
+            - X_SQUARE_EXPOSURE * (exposed_x_squares(self.current_player) - exposed_x_squares(self.current_player.opponent()))
+    }
+
+    /// The number of legal moves after which the game is over and won by the current player.
+    fn immediate_wins(&self) -> usize {
+        let sign = match self.current_player {
+            Player::One => 1,
+            Player::Two => -1,
+        };
+
+        self.move_indices()
+            .into_iter()
+            .filter(|mv| {
+                let mut game = self.clone();
+                game.play_idx(*mv).is_ok() && game.terminal_score().is_some_and(|score| score * sign > 0)
+            })
+            .count()
+    }
+
+    /// Scores the stability of the discs on the four edges for the current player, from `+4`
+    /// to `-4` per edge: half of the current player's stable edge discs minus the opponent's.
+    ///
+    /// A full edge can never change again, so all of its discs are stable, and an edge the
+    /// current player fills scores `+4`. On a partial edge only the unbroken runs from a
+    /// corner are counted, so it scores less.
+    pub fn eval_edge_stability(&self) -> isize {
+        let player = Cell::Player(self.current_player);
+        let opponent = Cell::Player(self.current_player.opponent());
+
+        let edges = [
+            (self.board.row_fill_mask(0), (0..WIDTH).map(|x| at_pos(x, 0)).collect::<Vec<_>>()),
+            (self.board.row_fill_mask(HEIGHT - 1), (0..WIDTH).map(|x| at_pos(x, HEIGHT - 1)).collect()),
+            (self.board.col_fill_mask(0), (0..HEIGHT).map(|y| at_pos(0, y)).collect()),
+            (self.board.col_fill_mask(WIDTH - 1), (0..HEIGHT).map(|y| at_pos(WIDTH - 1, y)).collect()),
+        ];
+
+        edges.into_iter().map(|(fill_mask, cells)| {
+            let cells = cells.into_iter().map(|idx| self.board.get_cell_idx(idx)).collect::<Vec<_>>();
+
+            // a disc in an unbroken run from a corner can't be flanked along the edge either
+            let stable = |idx: usize| {
+                fill_mask == u8::MAX || cells[..=idx].iter().all(|cell| *cell == cells[idx]) || cells[idx..].iter().all(|cell| *cell == cells[idx])
+            };
+
+            let count = |side: Cell| (0..cells.len()).filter(|idx| cells[*idx] == side && stable(*idx)).count() as isize;
+
+            (count(player) - count(opponent)) / 2
+        }).sum()
+    }
+
+    /// Counts how many squares of each [`CellType`] `player` occupies. Every type is present
+    /// in the map, even with a count of `0`.
+    pub fn count_by_cell_type(&self, player: Player) -> HashMap<CellType, usize> {
+        let mut counts = CellType::ALL.into_iter().map(|cell_type| (cell_type, 0)).collect::<HashMap<_, _>>();
+
+        for (idx, cell) in self.iter().enumerate() {
+            if cell == Cell::Player(player) {
+                *counts.entry(CellType::of(idx)).or_default() += 1;
+            }
+        }
+
+        counts
+    }
+
+    /// [`Game::evaluate`] mapped into `[-1.0, 1.0]` as `tanh(evaluate / 32)`.
+    ///
+    /// `tanh` is used over clamping `evaluate / 64` because the evaluation has no fixed
+    /// bound: clamping would flatten every clearly winning position to the same value, while
+    /// `tanh` keeps them ordered and only approaches the ends of the range.
+    pub fn normalized_eval(&self) -> f32 {
+        (self.evaluate() as f32 / 32.0).tanh()
+    }
+
+    /// Static evaluation of the position for the current player, as a weighted sum of the
+    /// features described on [`EvalWeights`].
+    pub fn eval_with_weights(&self, weights: &EvalWeights) -> isize {
+        let features = self.eval_features();
+
+        weights.to_array().iter().zip(features).map(|(weight, feature)| weight * feature).sum::<f32>().round() as isize
+    }
+
+    /// The unweighted features of [`EvalWeights`], in field order.
+    fn eval_features(&self) -> [f32; 6] {
+        let player = self.current_player;
+        let opponent = player.opponent();
+
+        let mut opponent_game = self.clone();
+        opponent_game.swap_players_unchecked();
+
+        let difference = |feature: &dyn Fn(Player) -> usize| feature(player) as f32 - feature(opponent) as f32;
+
+        [
+            difference(&|player| self.count_discs_for(player)),
+            self.count_legal_moves_naive() as f32 - opponent_game.count_legal_moves_naive() as f32,
+            difference(&|player| CORNERS.iter().filter(|idx| self.board.get_cell_idx(**idx) == Cell::Player(player)).count()),
+            difference(&|player| self.edge_stable_discs(player)),
+            difference(&|player| self.frontier_discs(player)),
+            if (SIZE - self.total_moves()) % 2 == 1 { 1.0 } else { -1.0 },
+        ]
+    }
+
+    /// Counts how often each square was played in `games`. Passes are ignored.
+    pub fn move_frequency_table(games: &[GameRecord]) -> [u32; SIZE] {
+        let mut table = [0; SIZE];
+
+        for idx in games.iter().flat_map(|record| record.moves.iter().flatten()) {
+            table[*idx] += 1;
+        }
+
+        table
+    }
+
+    /// [`Game::move_frequency_table`] scaled so the most played square is `1.0`. Every square
+    /// is `0.0` if no moves were played.
+    pub fn move_frequency_heatmap(games: &[GameRecord]) -> [f32; SIZE] {
+        let table = Self::move_frequency_table(games);
+        let max = table.iter().copied().max().unwrap_or(0).max(1) as f32;
+
+        table.map(|count| count as f32 / max)
+    }
+
+    /// Fits [`EvalWeights`] to a set of finished games with logistic regression.
+    ///
+    /// Every position in every game is a sample, labelled `1` if the player to move went on to
+    /// win, `0` if they lost and `0.5` for a draw. The win probability is predicted as
+    /// `sigmoid(evaluation / 16)`, and the weights are fitted by 50 iterations of gradient
+    /// descent on the cross-entropy, starting from the defaults. Records that can't be
+    /// replayed are skipped.
+    pub fn tune_weights(games: &[GameRecord]) -> EvalWeights {
+        const SCALE: f32 = 1.0 / 16.0;
+        const LEARNING_RATE: f32 = 1.0;
+        const ITERATIONS: usize = 50;
+
+        let mut samples = Vec::new();
+
+        for record in games {
+            let Ok(positions) = record.replay() else {
+                continue;
+            };
+
+            let Some(final_score) = positions.last().and_then(|game| game.terminal_score()) else {
+                continue;
+            };
+
+            for game in &positions {
+                let score = match game.current_player {
+                    Player::One => final_score,
+                    Player::Two => -final_score,
+                };
+
+                let label = match score.signum() {
+                    1 => 1.0,
+                    -1 => 0.0,
+                    _ => 0.5,
+                };
+
+                samples.push((game.eval_features(), label));
+            }
+        }
+
+        let mut weights = EvalWeights::default().to_array();
+
+        if samples.is_empty() {
+            return EvalWeights::from_array(weights);
+        }
+
+        for _ in 0..ITERATIONS {
+            let mut gradient = [0.0; 6];
+
+            for (features, label) in &samples {
+                let z: f32 = weights.iter().zip(features).map(|(weight, feature)| weight * feature).sum();
+                let prediction = 1.0 / (1.0 + (-z * SCALE).exp());
+
+                for (gradient, feature) in gradient.iter_mut().zip(features) {
+                    *gradient += (prediction - label) * SCALE * feature;
+                }
+            }
+
+            for (weight, gradient) in weights.iter_mut().zip(gradient) {
+                *weight -= LEARNING_RATE * gradient / samples.len() as f32;
+            }
+        }
+
+        EvalWeights::from_array(weights)
+    }
+
+    /// Counts a player's edge discs in an unbroken run from a corner they own.
+    fn edge_stable_discs(&self, player: Player) -> usize {
+        let mut stable = [false; SIZE];
+
+        for (corner_x, corner_y) in [(0, 0), (WIDTH - 1, 0), (0, HEIGHT - 1), (WIDTH - 1, HEIGHT - 1)] {
+            let x_dir: isize = if corner_x == 0 { 1 } else { -1 };
+            let y_dir: isize = if corner_y == 0 { 1 } else { -1 };
+
+            for (step_x, step_y) in [(x_dir, 0), (0, y_dir)] {
+                let (mut x, mut y) = (corner_x, corner_y);
+
+                while self.board.on_board(x, y) && self.board.get_cell(x, y) == Cell::Player(player) {
+                    stable[at_pos(x, y)] = true;
+
+                    x = x.wrapping_add_signed(step_x);
+                    y = y.wrapping_add_signed(step_y);
+                }
+            }
+        }
+
+        stable.iter().filter(|stable| **stable).count()
+    }
+
+    /// Counts a player's discs that border at least one empty square.
+    fn frontier_discs(&self, player: Player) -> usize {
+        (0..SIZE).filter(|idx| {
+            let (x, y) = (idx % WIDTH, idx / WIDTH);
+
+            self.board.get_cell(x, y) == Cell::Player(player) && DIRECTIONS.iter().any(|(x_dir, y_dir)| {
+                let (x, y) = (x.wrapping_add_signed(*x_dir), y.wrapping_add_signed(*y_dir));
+
+                self.board.on_board(x, y) && self.board.get_cell(x, y) == Cell::Empty
+            })
+        }).count()
+    }
+
+    /// Encodes this position as a [`TrainingSample`], scoring moves with a search of
+    /// `depth` plies.
+    pub fn to_training_sample(&self, depth: usize) -> TrainingSample {
+        let mut features = [0.0; 2 * SIZE];
+
+        for (idx, cell) in self.iter().enumerate() {
+            if cell == Cell::Player(self.current_player) {
+                features[2 * idx] = 1.0;
+            } else if cell == Cell::Player(self.current_player.opponent()) {
+                features[2 * idx + 1] = 1.0;
+            }
+        }
+
+        let scores = solve::solve_at_depth(self, depth);
+
+        let best_score = match scores.iter().map(|(score, _)| *score).max() {
+            Some(score) => score,
+            None => solve::negamax_depth(self, depth, -(SIZE as isize), SIZE as isize, &solve::disc_difference).unwrap(),
+        };
+
+        TrainingSample {
+            features,
+            value: (best_score as f32 / SIZE as f32).clamp(-1.0, 1.0),
+            policy: softmax_policy(&scores),
+        }
+    }
+
+    /// The board as a single feature plane, indexed as `[y][x]`: the current player's discs
+    /// are `1`, the opponent's `-1` and empty squares `0`.
+    pub fn to_feature_tensor(&self) -> [[f32; WIDTH]; HEIGHT] {
+        self.board.to_array().map(|row| row.map(|cell| match cell {
+            Cell::Player(player) if player == self.current_player => 1.0,
+            Cell::Player(_) => -1.0,
+            Cell::Empty => 0.0,
+        }))
+    }
+
+    /// The board as three one-hot feature planes, indexed as `[plane][y][x]`: player one's
+    /// discs, player two's discs, and empty squares.
+    pub fn to_multi_plane_features(&self) -> [[[f32; WIDTH]; HEIGHT]; 3] {
+        let array = self.board.to_array();
+
+        [Cell::Player(Player::One), Cell::Player(Player::Two), Cell::Empty]
+            .map(|plane| array.map(|row| row.map(|cell| if cell == plane { 1.0 } else { 0.0 })))
+    }
+
+    /// The probability of each square being the best move: a softmax over the
+    /// [`solve::solve_at_depth`] scores of the legal moves, with `0` for every other square.
+    /// If there are no legal moves, every square is `0`.
+    pub fn policy_vector(&self, depth: usize) -> [f32; SIZE] {
+        softmax_policy(&solve::solve_at_depth(self, depth))
+    }
+
+    /// Returns all 8 symmetric equivalents of this position, in the order of
+    /// [`Symmetry::ALL`].
+    ///
+    /// All of them share the same current player and game value.
+    pub fn make_symmetric_positions(&self) -> [Game; 8] {
+        Symmetry::ALL.map(|symmetry| Game::from_board(self.board.apply_symmetry(symmetry), self.current_player))
+    }
+
+    /// Maps a move on a board transformed by `sym` back to the same move on the original.
+    pub fn map_move_to_original(mv: usize, sym: Symmetry) -> usize {
+        sym.inverse().apply_to_index(mv)
+    }
+
+    /// Maps a move on the original board to the same move on the board transformed by `sym`.
+    /// This is the inverse of [`Game::map_move_to_original`].
+    pub fn map_move_to_symmetric(mv: usize, sym: Symmetry) -> usize {
+        sym.apply_to_index(mv)
+    }
+
+    /// Plays a move chosen on the board transformed by `sym` (for instance, a canonical form
+    /// used by the search) onto this, the original, game.
+    pub fn apply_move_mirrored(&mut self, mv: usize, sym: Symmetry) -> Result<()> {
+        self.play_idx(Self::map_move_to_original(mv, sym))
+    }
+}
+
+/// Maps `f` over `items` on scoped threads, one contiguous chunk per core, keeping order.
+fn par_map<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R> {
+    let threads = std::thread::available_parallelism().map_or(1, |threads| threads.get());
+    let chunk_size = items.len().div_ceil(threads).max(1);
+
+    std::thread::scope(|scope| {
+        let handles = items.chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        handles.into_iter().flat_map(|handle| handle.join().unwrap()).collect()
+    })
+}
+
+/// Softmax over scored moves, laid out by move index.
+fn softmax_policy(scores: &[(isize, usize)]) -> [f32; SIZE] {
+    let mut policy = [0.0; SIZE];
+
+    let Some(best_score) = scores.iter().map(|(score, _)| *score).max() else {
+        return policy;
+    };
+
+    // subtracting the best score keeps the exponentials from overflowing
+    let total: f32 = scores.iter().map(|(score, _)| ((score - best_score) as f32).exp()).sum();
+
+    for (score, idx) in scores {
+        policy[*idx] = ((score - best_score) as f32).exp() / total;
+    }
+
+    policy
+}
+
+/// Mutable access to a [`Game`]'s board, from [`Game::board_mut`]. The game's hash is
+/// recomputed when this is dropped.
+pub struct BoardMut<'a> {
+    game: &'a mut Game,
+}
+
+impl std::ops::Deref for BoardMut<'_> {
+    type Target = Board;
+
+    fn deref(&self) -> &Board {
+        &self.game.board
+    }
+}
+
+impl std::ops::DerefMut for BoardMut<'_> {
+    fn deref_mut(&mut self) -> &mut Board {
+        &mut self.game.board
+    }
+}
+
+impl Drop for BoardMut<'_> {
+    fn drop(&mut self) {
+        self.game.hash = self.game.compute_hash();
+    }
+}
+
+impl PartialEq for Game {
+    fn eq(&self, other: &Self) -> bool {
+        self.board == other.board && self.current_player == other.current_player
+    }
+}
+
+impl Eq for Game {}
+
+impl Clone for Game {
+    fn clone(&self) -> Self {
+        Game { board: self.board.clone(), current_player: self.current_player, hash: self.hash, history: self.history.clone() }
+    }
+
+    /// Copies the history into `self`'s existing buffer rather than allocating a new one, which
+    /// is what lets an `Arena` of games save allocations.
+    fn clone_from(&mut self, source: &Self) {
+        self.board.clone_from(&source.board);
+        self.current_player = source.current_player;
+        self.hash = source.hash;
+        self.history.clone_from(&source.history);
+    }
+}
+
+impl std::hash::Hash for Game {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        // the Zobrist hash already stands for the whole board
+        self.hash.hash(state);
+        self.current_player.hash(state);
+    }
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl fmt::Display for Game {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_score_overlay(&HashMap::new(), f)
     }
 }
 
 impl fmt::Debug for Game {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}:", Cell::Player(self.current_player).to_char())?;
+        write!(f, "{}:", self.current_player)?;
 
-        for cell in self.board.iter() {
-            write!(f, "{}", cell.to_char())?;
+        for cell in &self.board {
+            write!(f, "{}", cell)?;
         }
 
         Ok(())
@@ -1,6 +1,11 @@
-use clap::{Parser, Subcommand};
-use reversi_solver::{Game, solve::solve, board::{Player, Cell, WIDTH}};
-use anyhow::Result;
+use std::{path::PathBuf, time::Duration};
+
+use clap::{Parser, Subcommand, ValueEnum};
+use reversi_solver::{Game, solve::{solve, solve_at_depth, solve_with_timeout}, board::{Move, Player}, record::GameRecord};
+use anyhow::{Result, bail};
+
+/// Trees grow exponentially, so `--tree` is capped at this depth.
+const MAX_TREE_DEPTH: usize = 3;
 
 /// Solve and generate reversi puzzles
 #[derive(Parser, Debug)]
@@ -21,7 +26,67 @@ enum Commands {
         backtrack: usize
     },
     /// Solve a game
-    Solve
+    Solve {
+        /// Print the tree of positions up to this many plies deep instead of solving (at most 3)
+        #[arg(long)]
+        tree: Option<usize>,
+
+        /// Search as deep as possible within this many milliseconds, instead of to the end
+        #[arg(long)]
+        time: Option<u64>,
+    },
+    /// Load a position from a file and show it with shallow move scores
+    Import {
+        #[arg(short, long, value_enum)]
+        format: ImportFormat,
+
+        path: PathBuf,
+    },
+    /// Suggest the best 3 moves in a position, with a short explanation of each
+    Hint {
+        /// The board as 8 rows of `X`, `O` and `-`, separated by `/` or newlines
+        board: String,
+
+        /// The player to move, `X` or `O`
+        player: String,
+
+        /// How many plies past each move to search
+        #[arg(short, long, default_value_t = 4)]
+        depth: u8,
+    },
+    /// Play many games and print aggregate statistics about them
+    Stats {
+        /// How many games to play
+        #[arg(short, long, default_value_t = 100)]
+        games: usize,
+
+        /// Have both sides play the best move at this search depth instead of random moves
+        #[arg(short, long)]
+        depth: Option<usize>,
+    },
+    /// Play moves from the starting position and save the game to a JSON file
+    Save {
+        path: PathBuf,
+
+        /// The moves in algebraic notation, like `e3`, with `--` for a pass
+        moves: Vec<String>,
+    },
+    /// Load a game saved with `save` and show it with its moves
+    Load {
+        path: PathBuf,
+    },
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum ImportFormat {
+    /// Rows separated by `/`, with digits for empty runs, then the player: `8/8/8/3XO3/3OX3/8/8/8 X`
+    Fen,
+    /// The compressed format: `X:` or `O:` followed by all 64 cells
+    Compact,
+    /// A game saved with `save`
+    Json,
+    /// An Othello SGF game record
+    Sgf,
 }
 
 
@@ -32,11 +97,17 @@ fn main() -> Result<()> {
         Commands::Random { slow, backtrack } => {
             let mut game = Game::new();
 
-            let mut decided_moves: Vec<Option<usize>> = Vec::new();
+            let mut decided_moves: Vec<Option<Move>> = Vec::new();
 
-            let mut moves = game.moves();
+            while !game.is_game_over() {
+                let moves = game.moves();
+
+                if moves.is_empty() {
+                    game.pass()?;
+                    decided_moves.push(None);
+                    continue;
+                }
 
-            while moves.len() > 0 {
                 let move_index = fastrand::usize(..moves.len());
                 let chosen_move = moves[move_index];
 
@@ -46,32 +117,21 @@ fn main() -> Result<()> {
                     println!("{}", game);
                 }
 
-                game.play_idx(chosen_move).unwrap();
+                game.play_move(chosen_move).unwrap();
 
                 decided_moves.push(Some(chosen_move));
-
-                moves = game.moves();
-                if moves.len() == 0 {
-                    game.swap_players();
-                    moves = game.moves();
-                    decided_moves.push(None);
-                }
             }
 
-            let mut final_game = Game::new();
-            
-            for decided_move in &decided_moves[0..decided_moves.len() - backtrack] {
-                match decided_move {
-                    Some(idx) => final_game.play_idx(*idx).unwrap(),
-                    None => final_game.swap_players()
-                }
-            }
-            
+            let kept_moves = decided_moves[0..decided_moves.len() - backtrack].iter()
+                .map(|decided_move| decided_move.map(|chosen_move| chosen_move.index()))
+                .collect::<Vec<_>>();
+
+            let final_game = Game::from_moves(&kept_moves)?;
 
             println!("{}", final_game);
             println!("{:?}", final_game);
         },
-        Commands::Solve => {
+        Commands::Solve { tree, time } => {
             let game = Game::from_string("--OOOOOO\n\
             -**OOXXO\n\
             *-OOOOOO\n\
@@ -81,23 +141,110 @@ fn main() -> Result<()> {
             XOOXXOOO\n\
             *OXXXXO*", Player::One, true)?;
 
-            let scores = &solve(&game);
-
-            for (i, cell) in game.iter().enumerate() {
-                if let Some(score) = scores.into_iter().filter(|(_, idx)| *idx == i).map(|(_, score)| score).next() {
-                    print!("{:<3}", score);
-                } else {
-                    match cell {
-                        Cell::Empty => print!("-  "),
-                        Cell::Player(Player::One) => print!("X  "),
-                        Cell::Player(Player::Two) => print!("Y  "),
-                    }
+            if let Some(depth) = tree {
+                if depth > MAX_TREE_DEPTH {
+                    eprintln!("warning: tree depth {} is too large, limiting it to {}", depth, MAX_TREE_DEPTH);
+                }
+
+                print!("{}", game.to_move_tree_string(depth.min(MAX_TREE_DEPTH)));
+                return Ok(());
+            }
+
+            let scores = match time {
+                Some(time) => solve_with_timeout(&game, Duration::from_millis(time)).into_iter().map(|(score, idx)| (idx, score)).collect(),
+                None => solve(&game, None).into_iter().map(|(score, chosen_move)| (chosen_move.index(), score)).collect(),
+            };
+
+            print!("{}", game.score_overlay_string(&scores));
+        },
+        Commands::Import { format, path } => {
+            let contents = std::fs::read_to_string(path)?;
+
+            let game = match format {
+                ImportFormat::Fen => Game::from_fen(&contents)?,
+                ImportFormat::Compact => Game::from_compressed_string(contents.trim())?,
+                ImportFormat::Json => Game::from_json(&contents)?,
+                ImportFormat::Sgf => Game::from_sgf(&contents)?,
+            };
+
+            let scores = solve_at_depth(&game, 2).into_iter().map(|(score, idx)| (idx, score)).collect();
+
+            print!("{}", game.score_overlay_string(&scores));
+        },
+        Commands::Hint { board, player, depth } => {
+            let player = match player.chars().collect::<Vec<_>>()[..] {
+                [character] => Player::try_from(character)?,
+                _ => bail!("Invalid player: {}, expected X or O", player),
+            };
+
+            let game = Game::from_string(&board.replace('/', "\n"), player, false)?;
+
+            let mut scores = solve_at_depth(&game, depth as usize);
+
+            if scores.is_empty() {
+                println!("{}", game.explain_pass());
+                return Ok(());
+            }
+
+            scores.sort_by_key(|(score, _)| -score);
+
+            for (score, idx) in scores.into_iter().take(3) {
+                println!("Move {}: {}, score: {:+}", Game::move_to_san(idx), game.explain_move(idx)?, score);
+            }
+        },
+        Commands::Stats { games, depth } => {
+            if games == 0 {
+                bail!("At least one game is needed");
+            }
+
+            let choose_move = |game: &Game| match depth {
+                Some(depth) => game.top_k_moves(1, depth).first().copied(),
+                None => {
+                    let moves = game.move_indices();
+                    Some(moves[fastrand::usize(..moves.len())])
                 }
+            };
+
+            let (mut moves, mut passes, mut differential, mut first_player_wins) = (0, 0, 0, 0);
+
+            for _ in 0..games {
+                let (game, history) = Game::auto_play_game(choose_move, choose_move);
+                let score = game.terminal_score().unwrap_or_default();
 
-                if i % WIDTH == WIDTH - 1 {
-                    println!();
+                moves += history.iter().flatten().count();
+                passes += history.iter().filter(|recorded_move| recorded_move.is_none()).count();
+                differential += score;
+
+                if score > 0 {
+                    first_player_wins += 1;
                 }
             }
+
+            let average = |total: f64| total / games as f64;
+
+            println!("Games: {}", games);
+            println!("Average length: {:.1} moves", average(moves as f64));
+            println!("Average disc differential (X - O): {:+.1}", average(differential as f64));
+            println!("First player win rate: {:.1}%", 100.0 * average(first_player_wins as f64));
+            println!("Average passes: {:.1}", average(passes as f64));
+        },
+        Commands::Save { path, moves } => {
+            let moves = moves.iter()
+                .map(|san| if san == "--" { Ok(None) } else { Game::san_to_move(san).map(Some) })
+                .collect::<Result<Vec<_>>>()?;
+
+            let game = Game::from_moves(&moves)?;
+
+            std::fs::write(path, game.to_json())?;
+
+            println!("{}", game);
+        },
+        Commands::Load { path } => {
+            let game = Game::from_json(&std::fs::read_to_string(path)?)?;
+            let moves = game.move_history().iter().map(|entry| entry.move_idx).collect();
+
+            println!("{}", game);
+            print!("{}", GameRecord::new(moves));
         }
     };
 
@@ -0,0 +1,58 @@
+use std::fmt;
+
+use crate::Game;
+use anyhow::{Result, anyhow};
+
+/// A recorded game, played from the standard starting position.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameRecord {
+    /// Each move in order, or `None` for a pass.
+    pub moves: Vec<Option<usize>>,
+}
+
+/// Who played a game, and when and where, as recorded in the tags of a PGN game.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GameMetadata {
+    pub black_player: String,
+    pub white_player: String,
+    /// In PGN's `YYYY.MM.DD` format.
+    pub date: Option<String>,
+    pub event: Option<String>,
+}
+
+impl GameRecord {
+    pub fn new(moves: Vec<Option<usize>>) -> Self {
+        GameRecord { moves }
+    }
+
+    /// Replays the record, returning every position from the start to the final one.
+    pub fn replay(&self) -> Result<Vec<Game>> {
+        let mut game = Game::new();
+        let mut positions = vec![game.clone()];
+
+        for (number, recorded_move) in self.moves.iter().enumerate() {
+            match recorded_move {
+                Some(idx) => game.play_idx(*idx).map_err(|err| anyhow!("Move {}: {}", number + 1, err))?,
+                None => game.pass().map_err(|err| anyhow!("Move {}: {}", number + 1, err))?,
+            }
+
+            positions.push(game.clone());
+        }
+
+        Ok(positions)
+    }
+}
+
+impl fmt::Display for GameRecord {
+    /// One ply per line, like `1. (Black) e3`, with `--` for a pass.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (ply, recorded_move) in self.moves.iter().enumerate() {
+            match recorded_move {
+                Some(idx) => writeln!(f, "{} {}", Game::ply_to_notation(ply), Game::move_to_san(*idx))?,
+                None => writeln!(f, "{} --", Game::ply_to_notation(ply))?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -1,43 +1,605 @@
-use crate::{Game, board::SIZE};
-use anyhow::Result;
+#[cfg(feature = "arena")]
+use std::cell::RefCell;
+use std::{collections::HashMap, time::{Duration, Instant}};
 
-pub fn negamax(game: &Game) -> Result<isize> {
-    let moves = &game.moves();
+use crate::{Game, board::{Board, Cell, Move, Player, CORNER_MASK, SIZE, X_SQUARE_MASK}, eval::EvaluationFn};
+#[cfg(feature = "arena")]
+use crate::arena::Arena;
+use anyhow::{Result, anyhow};
+
+/// The number of empty squares at or below which a position can be solved exactly without
+/// an undue wait.
+pub const ENDGAME_THRESHOLD: usize = 10;
+
+/// Positions with more empty squares than this are treated as openings, where searching is
+/// of little use.
+pub const OPENING_THRESHOLD: usize = 50;
+
+/// How a position is best approached, depending on the phase of the game.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SolverStrategy {
+    /// Opening: sample random playouts to compare moves.
+    RandomPlayout,
+    /// Midgame: a depth-limited search with a heuristic evaluation, to the given depth.
+    HeuristicSearch(usize),
+    /// Endgame: an exact search to the end of the game.
+    PerfectEndgame,
+}
+
+#[cfg(feature = "arena")]
+thread_local! {
+    /// The games this thread's searches play on, kept between searches.
+    static SEARCH_ARENA: RefCell<Arena<Game>> = RefCell::new(Arena::new());
+}
+
+/// A copy of `game` for a search to play moves on and undo them. With the `arena` feature it
+/// is taken from a per-thread [`Arena`], and [`release_search_game`] hands it back.
+#[cfg(feature = "arena")]
+pub(crate) fn search_game(game: &Game) -> Game {
+    SEARCH_ARENA.with_borrow_mut(|arena| arena.get(game))
+}
+
+#[cfg(not(feature = "arena"))]
+pub(crate) fn search_game(game: &Game) -> Game {
+    game.clone()
+}
+
+/// Returns a game from [`search_game`] once the search is done with it.
+#[cfg(feature = "arena")]
+pub(crate) fn release_search_game(game: Game) {
+    SEARCH_ARENA.with_borrow_mut(|arena| arena.release(game));
+}
+
+#[cfg(not(feature = "arena"))]
+pub(crate) fn release_search_game(_game: Game) {}
+
+/// Orders `moves` so alpha-beta tries the likely best first: corners, then other moves by
+/// the number of discs they flip (most first), then X-squares, which usually hand the
+/// opponent a corner. Moves keep their relative order within each group.
+///
+/// The X-squares are the cells diagonally inside each corner ([`X_SQUARE_MASK`]: 9, 14, 49
+/// and 54). The C-squares beside the corners on the edges
+/// ([`C_SQUARE_MASK`](crate::board::C_SQUARE_MASK): 1, 6, 8, 15, 48, 55, 57 and 62) are
+/// ordered by flips like any other move, as taking one is often safe once the corner is
+/// settled.
+pub fn order_moves(game: &Game, moves: &[usize]) -> Vec<usize> {
+    let is_corner = |mv: &&usize| CORNER_MASK & 1 << **mv != 0;
+    let is_x_square = |mv: &&usize| X_SQUARE_MASK & 1 << **mv != 0;
+
+    let mut middle = moves.iter().filter(|mv| !is_corner(mv) && !is_x_square(mv)).copied().collect::<Vec<_>>();
+    middle.sort_by_key(|mv| std::cmp::Reverse(game.count_flips(*mv)));
+
+    moves.iter().filter(is_corner).copied()
+        .chain(middle)
+        .chain(moves.iter().filter(is_x_square).copied())
+        .collect()
+}
+
+/// Searches `game` to the end within the `(alpha, beta)` window, skipping moves that cannot
+/// improve on a score already guaranteed. Scores inside the window are exact.
+///
+/// The score is not a disc difference: a move that ends the game with the player to move
+/// ahead scores `(SIZE + 1 - total_moves) / 2`, so earlier wins score higher, and a position
+/// without a legal move scores `0`. [`solve`] and [`Game::endgame_search_with_empties`]
+/// give the final disc difference instead.
+pub fn negamax(game: &Game, alpha: isize, beta: isize) -> Result<isize> {
+    negamax_with_table(game, alpha, beta, &mut TranspositionTable::new())
+}
+
+/// [`negamax`], storing the result of every expanded position in `tt` and consulting it
+/// before expanding a position again. The table can be shared between searches.
+pub fn negamax_with_table(game: &Game, alpha: isize, beta: isize, tt: &mut TranspositionTable) -> Result<isize> {
+    let mut game = search_game(game);
+    let score = negamax_in_place(&mut game, alpha, beta, tt);
+    release_search_game(game);
+
+    score
+}
+
+/// [`negamax_with_table`], playing each move on `game` and undoing it afterwards instead of
+/// cloning the position for every child.
+fn negamax_in_place(game: &mut Game, mut alpha: isize, mut beta: isize, tt: &mut TranspositionTable) -> Result<isize> {
+    let moves = &game.move_indices();
 
     if moves.is_empty() {
         return Ok(0);
     }
 
+    let player = game.current_player;
+
+    // as `Game::is_winning_move_idx`, without cloning the game for every move
     for possible_move in moves {
-        if game.is_winning_move_idx(*possible_move, game.current_player)? {
+        game.play_idx(*possible_move)?;
+        let wins = game.winning_player() == Some(player) && game.move_indices().is_empty();
+        game.undo()?;
+
+        if wins {
             return Ok((SIZE as isize + 1 - game.total_moves() as isize) / 2);
         }
     }
 
-    let mut best_score = -(SIZE as isize);
+    // the search always runs to the end of the game, so the remaining depth is the number
+    // of empty squares
+    let depth = SIZE - game.total_moves();
+    let original_alpha = alpha;
 
-    for possible_move in moves {
-        let mut new_game = game.clone();
+    if let Some(entry) = tt.get(game, depth) {
+        match entry.flag {
+            TTFlag::Exact => return Ok(entry.score),
+            TTFlag::LowerBound => alpha = alpha.max(entry.score),
+            TTFlag::UpperBound => beta = beta.min(entry.score),
+        }
+
+        if alpha >= beta {
+            return Ok(entry.score);
+        }
+    }
+
+    let mut best_score = -(SIZE as isize);
+    let mut best_move = None;
 
-        new_game.play_idx(*possible_move)?;
-    
-        let score = -negamax(&new_game)?;
+    for possible_move in tt_ordered_moves(game, moves, tt) {
+        game.play_idx(possible_move)?;
+        let score = -negamax_in_place(game, -beta, -alpha, tt)?;
+        game.undo()?;
 
         if score > best_score {
             best_score = score;
+            best_move = Some(possible_move);
+        }
+
+        if best_score > alpha {
+            alpha = best_score;
+        }
+
+        if alpha >= beta {
+            break;
         }
     }
 
+    let flag = if best_score <= original_alpha {
+        TTFlag::UpperBound
+    } else if best_score >= beta {
+        TTFlag::LowerBound
+    } else {
+        TTFlag::Exact
+    };
+
+    tt.insert(game, TTEntry { depth: depth as u8, score: best_score, flag, best_move });
+
     Ok(best_score)
 }
 
-/// Returns the scores for each move
-pub fn solve(game: &Game) -> Vec<(isize, usize)> {
+/// [`order_moves`], with the best move `tt` has stored for `game` moved to the front.
+fn tt_ordered_moves(game: &Game, moves: &[usize], tt: &TranspositionTable) -> Vec<usize> {
+    let mut ordered = order_moves(game, moves);
+
+    if let Some(position) = tt.best_move(game).and_then(|best_move| ordered.iter().position(|mv| *mv == best_move)) {
+        ordered[..=position].rotate_right(1);
+    }
+
+    ordered
+}
+
+/// [`Game::legal_turns`], with the moves put in the order `order` gives them. A forced pass
+/// is the only turn there is, so it is left as it is.
+fn ordered_turns(game: &Game, order: impl FnOnce(&[usize]) -> Vec<usize>) -> Vec<Option<usize>> {
+    let moves = game.move_indices();
+
+    if moves.is_empty() {
+        return game.legal_turns();
+    }
+
+    order(&moves).into_iter().map(Some).collect()
+}
+
+/// Returns the scores for each move, as the final disc difference for the player making it.
+/// With no `depth`, every move is searched to the end of the game, sharing one
+/// [`TranspositionTable`] across the searches, so the scores are exact. With a `depth`, this
+/// is [`solve_at_depth`]; searching at least as deep as there are empty squares gives the
+/// same scores as no `depth`.
+pub fn solve(game: &Game, depth: Option<usize>) -> Vec<(isize, Move)> {
+    if let Some(depth) = depth {
+        return solve_at_depth(game, depth).into_iter().map(|(score, idx)| (score, Move::from_index(idx))).collect();
+    }
+
+    let mut tt = TranspositionTable::new();
+    let mut new_game = search_game(game);
+
     // turn each possible move into a score and get the top ones
-    game.moves().into_iter().map(|possible_move| {
-        let mut new_game = game.clone();
-        new_game.play_idx(possible_move).unwrap();
-        let score = negamax(&new_game).unwrap();
+    let scores = game.moves().into_iter().map(|possible_move| {
+        new_game.play_move(possible_move).unwrap();
+        let score = -endgame_score(&mut new_game, &mut tt).unwrap();
+        new_game.undo().unwrap();
+
+        (score, possible_move)
+    }).collect();
+
+    release_search_game(new_game);
+    scores
+}
+
+/// The exact final disc difference for the player to move in `game`, searched to the end of
+/// the game by [`alpha_beta_tt`] with `tt`.
+pub(crate) fn endgame_score(game: &mut Game, tt: &mut TranspositionTable) -> Result<isize> {
+    // passes don't use up depth, so one ply per empty square reaches the end of every line
+    alpha_beta_tt(game, SIZE - game.total_moves(), -(SIZE as isize) - 1, SIZE as isize + 1, tt, None)
+}
+
+/// Disc difference from the point of view of the player to move: the default evaluation for
+/// [`negamax_depth`].
+pub fn disc_difference(game: &Game) -> isize {
+    let player = Cell::Player(game.current_player);
+    let opponent = Cell::Player(game.current_player.opponent());
+
+    game.iter().map(|cell| match cell {
+        cell if cell == player => 1,
+        cell if cell == opponent => -1,
+        _ => 0,
+    }).sum()
+}
+
+/// The exact [`Game::terminal_score`] from the point of view of the player to move.
+fn terminal_value(game: &Game) -> Option<isize> {
+    game.terminal_score().map(|score| match game.current_player {
+        Player::One => score,
+        Player::Two => -score,
+    })
+}
+
+/// Depth-limited fail-soft alpha-beta negamax, scoring the positions it stops at with the
+/// [`EvaluationFn`] `eval` (from the point of view of the player to move). Finished games
+/// are scored exactly with [`Game::terminal_score`]. Passes do not count towards the depth.
+/// Scores inside the `(alpha, beta)` window are exact.
+pub fn negamax_depth(game: &Game, depth: usize, alpha: isize, beta: isize, eval: &dyn EvaluationFn) -> Result<isize> {
+    let mut game = search_game(game);
+    let score = negamax_depth_in_place(&mut game, depth, alpha, beta, eval);
+    release_search_game(game);
+
+    score
+}
+
+/// [`negamax_depth`], taking each of [`Game::legal_turns`] on `game` and undoing it
+/// afterwards instead of cloning the position for every child.
+fn negamax_depth_in_place(game: &mut Game, depth: usize, mut alpha: isize, beta: isize, eval: &dyn EvaluationFn) -> Result<isize> {
+    if let Some(score) = terminal_value(game) {
+        return Ok(score);
+    }
+
+    if depth == 0 {
+        return Ok(eval.evaluate(game));
+    }
+
+    let mut best_score = -isize::MAX;
+
+    for turn in game.legal_turns() {
+        // a pass leaves the board as it was, and does not use up any depth
+        let new_depth = if turn.is_some() { depth - 1 } else { depth };
+
+        game.play_turn(turn)?;
+        let score = -negamax_depth_in_place(game, new_depth, -beta, -alpha, eval)?;
+        game.undo()?;
+
+        if score > best_score {
+            best_score = score;
+        }
+
+        if score > alpha {
+            alpha = score;
+        }
+
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    Ok(best_score)
+}
+
+/// [`negamax_depth`] scored by [`disc_difference`], searching on a game taken from `arena`
+/// rather than from the per-thread pool.
+#[cfg(feature = "arena")]
+pub fn negamax_depth_arena(game: &Game, depth: usize, arena: &mut Arena<Game>) -> Result<isize> {
+    let mut game = arena.get(game);
+    let score = negamax_depth_in_place(&mut game, depth, -(SIZE as isize), SIZE as isize, &disc_difference);
+    arena.release(game);
+
+    score
+}
+
+/// [`solve_at_depth`], searching on a game taken from `arena` rather than from the
+/// per-thread pool.
+#[cfg(feature = "arena")]
+pub fn solve_at_depth_arena(game: &Game, depth: usize, arena: &mut Arena<Game>) -> Vec<(isize, usize)> {
+    let mut game = arena.get(game);
+    let scores = solve_at_depth_in_place(&mut game, depth);
+    arena.release(game);
+
+    scores
+}
+
+/// Returns the scores for each move, searching `depth` plies past the move itself and scoring
+/// by [`disc_difference`].
+pub fn solve_at_depth(game: &Game, depth: usize) -> Vec<(isize, usize)> {
+    let mut game = search_game(game);
+    let scores = solve_at_depth_in_place(&mut game, depth);
+    release_search_game(game);
+
+    scores
+}
+
+/// [`solve_at_depth`], playing each move on `game` and undoing it afterwards.
+fn solve_at_depth_in_place(game: &mut Game, depth: usize) -> Vec<(isize, usize)> {
+    game.move_indices().into_iter().map(|possible_move| {
+        game.play_idx(possible_move).unwrap();
+        let score = -negamax_depth_in_place(game, depth, -(SIZE as isize), SIZE as isize, &disc_difference).unwrap();
+        game.undo().unwrap();
+
         (score, possible_move)
     }).collect()
-}
\ No newline at end of file
+}
+
+
+/// Fail-soft alpha-beta search to the end of the game, scored by final disc difference for
+/// the player to move. Each move is played on `game` and undone afterwards, so `game` is
+/// left as it was unless the search fails.
+pub(crate) fn alpha_beta(game: &mut Game, mut alpha: isize, beta: isize) -> Result<isize> {
+    if let Some(score) = terminal_value(game) {
+        return Ok(score);
+    }
+
+    let mut best_score = -(SIZE as isize);
+
+    for turn in ordered_turns(game, |moves| order_moves(game, moves)) {
+        game.play_turn(turn)?;
+        let score = -alpha_beta(game, -beta, -alpha)?;
+        game.undo()?;
+
+        if score >= beta {
+            return Ok(score);
+        }
+
+        if score > best_score {
+            best_score = score;
+        }
+
+        if score > alpha {
+            alpha = score;
+        }
+    }
+
+    Ok(best_score)
+}
+
+/// How a stored score relates to the true value of a position.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum TTFlag {
+    Exact,
+    LowerBound,
+    UpperBound,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct TTEntry {
+    /// The remaining search depth the score was computed with.
+    pub depth: u8,
+    pub score: isize,
+    pub flag: TTFlag,
+    /// The move that scored best, if any move was searched.
+    pub best_move: Option<usize>,
+}
+
+/// Caches search results by position so transpositions are only searched once.
+///
+/// Entries are only reused at the exact depth they were stored with, so searching with a
+/// table always gives the same scores as searching without one.
+#[derive(Clone, Debug, Default)]
+pub struct TranspositionTable {
+    /// Keyed by board and player to move rather than by [`Game`], so storing a position
+    /// doesn't copy its move history.
+    entries: HashMap<(Board, Player), TTEntry>,
+}
+
+impl TranspositionTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, game: &Game, depth: usize) -> Option<TTEntry> {
+        self.entries.get(&Self::key(game)).copied().filter(|entry| entry.depth as usize == depth)
+    }
+
+    /// The best move stored for `game` at any depth. Unlike scores, a move found by a
+    /// shallower search is still a good first guess for a deeper one.
+    pub fn best_move(&self, game: &Game) -> Option<usize> {
+        self.entries.get(&Self::key(game)).and_then(|entry| entry.best_move)
+    }
+
+    pub fn insert(&mut self, game: &Game, entry: TTEntry) {
+        self.entries.insert(Self::key(game), entry);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    fn key(game: &Game) -> (Board, Player) {
+        (game.board().clone(), game.current_player)
+    }
+}
+
+/// Depth-limited fail-soft alpha-beta that stores and consults bounds in `tt`. Scores match
+/// [`negamax_depth`] whenever they fall inside the window.
+///
+/// If `deadline` passes during the search, it is abandoned with an error. Each move is
+/// played on `game` and undone afterwards, so `game` is left as it was unless the search
+/// fails.
+fn alpha_beta_tt(game: &mut Game, depth: usize, mut alpha: isize, mut beta: isize, tt: &mut TranspositionTable, deadline: Option<Instant>) -> Result<isize> {
+    if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+        Err(anyhow!("Search timed out"))?;
+    }
+
+    if let Some(score) = terminal_value(game) {
+        return Ok(score);
+    }
+
+    if depth == 0 {
+        return Ok(disc_difference(game));
+    }
+
+    let original_alpha = alpha;
+
+    if let Some(entry) = tt.get(game, depth) {
+        match entry.flag {
+            TTFlag::Exact => return Ok(entry.score),
+            TTFlag::LowerBound => alpha = alpha.max(entry.score),
+            TTFlag::UpperBound => beta = beta.min(entry.score),
+        }
+
+        if alpha >= beta {
+            return Ok(entry.score);
+        }
+    }
+
+    let mut best_score = -(SIZE as isize);
+    let mut best_move = None;
+
+    for turn in ordered_turns(game, |moves| tt_ordered_moves(game, moves, tt)) {
+        // a pass leaves the board as it was, and does not use up any depth
+        let new_depth = if turn.is_some() { depth - 1 } else { depth };
+
+        game.play_turn(turn)?;
+        let score = -alpha_beta_tt(game, new_depth, -beta, -alpha, tt, deadline)?;
+        game.undo()?;
+
+        if score > best_score {
+            best_score = score;
+            best_move = turn;
+        }
+
+        if score > alpha {
+            alpha = score;
+        }
+
+        if alpha >= beta {
+            break;
+        }
+    }
+
+    if depth <= u8::MAX as usize {
+        let flag = if best_score <= original_alpha {
+            TTFlag::UpperBound
+        } else if best_score >= beta {
+            TTFlag::LowerBound
+        } else {
+            TTFlag::Exact
+        };
+
+        tt.insert(game, TTEntry { depth: depth as u8, score: best_score, flag, best_move });
+    }
+
+    Ok(best_score)
+}
+
+/// Scores every move like [`solve_at_depth`] with `max_depth`, by searching each depth from
+/// 1 up to `max_depth` in turn. One [`TranspositionTable`] is kept throughout, so the best
+/// moves of each depth are tried first at the next, and root moves are searched best first
+/// by the previous depth's scores. Stopping after any depth still leaves a complete answer
+/// for that depth.
+///
+/// The scores are returned in the same order as [`Game::moves`].
+pub fn iterative_deepening_solve(game: &Game, max_depth: usize) -> Result<Vec<(isize, usize)>> {
+    if max_depth == 0 {
+        Err(anyhow!("Iterative deepening needs a depth of at least 1"))?;
+    }
+
+    let mut tt = TranspositionTable::new();
+    let mut scores = game.move_indices().into_iter().map(|possible_move| (0, possible_move)).collect::<Vec<_>>();
+
+    for depth in 1..=max_depth {
+        scores = deepen(game, depth, &scores, &mut tt, None)?;
+    }
+
+    Ok(scores)
+}
+
+/// Rescores the root moves in `scores` at `depth`, searching them best first by their
+/// current scores. The scores are returned in the same order as `scores`.
+fn deepen(game: &Game, depth: usize, scores: &[(isize, usize)], tt: &mut TranspositionTable, deadline: Option<Instant>) -> Result<Vec<(isize, usize)>> {
+    let mut search_order = scores.to_vec();
+    search_order.sort_by_key(|(score, _)| -score);
+
+    let mut depth_scores = Vec::with_capacity(search_order.len());
+
+    let mut game = search_game(game);
+
+    for (_, possible_move) in search_order {
+        game.play_idx(possible_move)?;
+        let score = -alpha_beta_tt(&mut game, depth, -(SIZE as isize), SIZE as isize, tt, deadline)?;
+        game.undo()?;
+
+        depth_scores.push((score, possible_move));
+    }
+
+    release_search_game(game);
+
+    Ok(scores.iter().map(|(_, possible_move)| *depth_scores.iter().find(|(_, searched)| searched == possible_move).unwrap()).collect())
+}
+
+/// Scores every move like [`solve_at_depth`], at the greatest depth that can be searched
+/// before `timeout` runs out. Depths are searched in turn as in [`iterative_deepening_solve`];
+/// a depth that runs out of time is abandoned, and the scores of the last completed depth
+/// are returned. If not even depth 1 completes, the moves are scored by [`disc_difference`]
+/// right after they are played.
+///
+/// The scores are returned in the same order as [`Game::moves`].
+pub fn solve_with_timeout(game: &Game, timeout: Duration) -> Vec<(isize, usize)> {
+    let deadline = Instant::now() + timeout;
+
+    let mut tt = TranspositionTable::new();
+    let mut scores = solve_at_depth(game, 0);
+
+    // passes don't use up depth, so past the number of empty squares every line reaches
+    // the end of the game and searching deeper changes nothing
+    for depth in 1..=SIZE - game.total_moves() {
+        match deepen(game, depth, &scores, &mut tt, Some(deadline)) {
+            Ok(depth_scores) => scores = depth_scores,
+            Err(_) => break,
+        }
+    }
+
+    scores
+}
+
+/// MTD(f): converges on the depth-limited negamax score of `game` through a series of
+/// null-window searches, starting from `first_guess`. The closer the guess, the fewer
+/// searches are needed; `tt` carries bounds between them.
+pub fn negamax_mtdf(game: &Game, first_guess: isize, depth: usize, tt: &mut TranspositionTable) -> Result<isize> {
+    let mut score = first_guess;
+    let mut lower_bound = -(SIZE as isize);
+    let mut upper_bound = SIZE as isize;
+    let mut game = search_game(game);
+
+    while lower_bound < upper_bound {
+        let beta = if score == lower_bound { score + 1 } else { score };
+
+        score = alpha_beta_tt(&mut game, depth, beta - 1, beta, tt, None)?;
+
+        if score < beta {
+            upper_bound = score;
+        } else {
+            lower_bound = score;
+        }
+    }
+
+    release_search_game(game);
+    Ok(score)
+}
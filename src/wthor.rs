@@ -0,0 +1,93 @@
+use anyhow::{Result, anyhow};
+
+use crate::{Game, board::{WIDTH, HEIGHT}, record::GameRecord};
+
+const HEADER_SIZE: usize = 16;
+const GAME_SIZE: usize = 68;
+const MOVES: usize = 60;
+
+/// One game from a WThor database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct WThorGame {
+    /// The year the games in the database were played, from the file header.
+    pub year: u16,
+    /// The tournament's index in the database's tournament list (`WTHOR.TRN`).
+    pub tournament: u16,
+    /// Black's index in the database's player list (`WTHOR.JOU`).
+    pub black_player: u16,
+    /// White's index in the player list.
+    pub white_player: u16,
+    /// The number of discs Black finished with.
+    pub black_score: u8,
+    /// Black's disc count with perfect play from the point the database was solved from.
+    pub theoretical_score: u8,
+    /// Every move played, as `10 * row + column` with both counted from 1, so `56` is f5.
+    /// Passes are not recorded.
+    pub moves: Vec<u8>,
+}
+
+/// Parses every game in a WThor (`.wtb`) database, the standard archive format for
+/// tournament Othello games: a 16 byte header followed by one 68 byte record per game.
+/// Returns an error if the file is truncated, its header doesn't match the number of
+/// records, or it holds games on a board other than 8x8.
+pub fn parse(bytes: &[u8]) -> Result<Vec<WThorGame>> {
+    let (header, records) = bytes.split_at_checked(HEADER_SIZE).ok_or(anyhow!("Missing the {} byte header", HEADER_SIZE))?;
+
+    let games = u32::from_le_bytes([header[4], header[5], header[6], header[7]]) as usize;
+    let year = u16::from_le_bytes([header[10], header[11]]);
+
+    if !matches!(header[12], 0 | 8) {
+        Err(anyhow!("Unsupported board size: {}", header[12]))?;
+    }
+
+    if records.len() != games * GAME_SIZE {
+        Err(anyhow!("The header lists {} games ({} bytes), found {} bytes", games, games * GAME_SIZE, records.len()))?;
+    }
+
+    Ok(records.chunks_exact(GAME_SIZE).map(|record| WThorGame {
+        year,
+        tournament: u16::from_le_bytes([record[0], record[1]]),
+        black_player: u16::from_le_bytes([record[2], record[3]]),
+        white_player: u16::from_le_bytes([record[4], record[5]]),
+        black_score: record[6],
+        theoretical_score: record[7],
+        moves: record[8..8 + MOVES].iter().copied().take_while(|square| *square != 0).collect(),
+    }).collect())
+}
+
+impl WThorGame {
+    /// The game as a [`GameRecord`], with the passes WThor leaves out put back in.
+    ///
+    /// This crate's starting position is the mirror image of the standard one, so squares
+    /// are mirrored left to right: f5, the usual first move, becomes c5.
+    pub fn to_record(&self) -> Result<GameRecord> {
+        let mut game = Game::new();
+        let mut moves = Vec::with_capacity(self.moves.len());
+
+        for (number, square) in self.moves.iter().enumerate() {
+            let (row, column) = (*square as usize / 10, *square as usize % 10);
+
+            if !(1..=HEIGHT).contains(&row) || !(1..=WIDTH).contains(&column) {
+                Err(anyhow!("Move {}: invalid square {}", number + 1, square))?;
+            }
+
+            let idx = (row - 1) * WIDTH + WIDTH - column;
+
+            if game.move_indices().is_empty() {
+                game.pass()?;
+                moves.push(None);
+            }
+
+            game.play_idx(idx).map_err(|err| anyhow!("Move {} ({}): {}", number + 1, square, err))?;
+            moves.push(Some(idx));
+        }
+
+        Ok(GameRecord::new(moves))
+    }
+
+    /// Replays the game, returning every position from the start to the final one, including
+    /// those where a player passed.
+    pub fn replay(&self) -> Result<Vec<Game>> {
+        self.to_record()?.replay()
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::LazyLock;
+
+use crate::board::{Board, Cell, Player, SIZE};
+
+/// The seed of [`ZOBRIST_TABLE`], fixed so hashes are the same on every run.
+const ZOBRIST_SEED: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// The keys behind [`Board::compute_hash`] and [`crate::Game::hash`], indexed by cell and
+/// then by cell state (empty, player one, player two).
+pub static ZOBRIST_TABLE: LazyLock<[[u64; 3]; SIZE]> = LazyLock::new(|| random_keys(ZOBRIST_SEED));
+
+/// The key [`crate::Game::hash`] includes when Player Two is to move, so positions that only
+/// differ in whose turn it is hash differently.
+pub static ZOBRIST_PLAYER_TWO: LazyLock<u64> = LazyLock::new(|| fastrand::Rng::with_seed(!ZOBRIST_SEED).u64(..));
+
+/// The side-to-move part of a game's hash: [`ZOBRIST_PLAYER_TWO`] for Player Two, and nothing
+/// for Player One.
+pub(crate) fn side_key(player: Player) -> u64 {
+    match player {
+        Player::One => 0,
+        Player::Two => *ZOBRIST_PLAYER_TWO,
+    }
+}
+
+fn random_keys(seed: u64) -> [[u64; 3]; SIZE] {
+    let mut rng = fastrand::Rng::with_seed(seed);
+    let mut keys = [[0; 3]; SIZE];
+
+    for cell_keys in keys.iter_mut() {
+        for key in cell_keys.iter_mut() {
+            *key = rng.u64(..);
+        }
+    }
+
+    keys
+}
+
+/// The index of `cell`'s state in a row of [`ZOBRIST_TABLE`].
+pub(crate) fn cell_state(cell: Cell) -> usize {
+    match cell {
+        Cell::Empty => 0,
+        Cell::Player(Player::One) => 1,
+        Cell::Player(Player::Two) => 2,
+    }
+}
+
+/// Random keys for Zobrist hashing a board: one for every state of every cell. A board's hash
+/// is the XOR of the keys of its cells, so changing one cell only takes two XORs.
+#[derive(Clone, Debug)]
+pub struct ZobristHasher {
+    keys: [[u64; 3]; SIZE],
+}
+
+impl ZobristHasher {
+    /// Generates the keys from `seed`, so the same seed always gives the same hashes.
+    pub fn new(seed: u64) -> Self {
+        ZobristHasher { keys: random_keys(seed) }
+    }
+
+    /// The key for `cell` being at `idx`.
+    pub fn key(&self, idx: usize, cell: Cell) -> u64 {
+        self.keys[idx][cell_state(cell)]
+    }
+
+    /// Hashes `board` from scratch.
+    pub fn hash(&self, board: &Board) -> u64 {
+        board.iter().enumerate().fold(0, |hash, (idx, cell)| hash ^ self.key(idx, *cell))
+    }
+}
@@ -0,0 +1,40 @@
+#![cfg(feature = "arena")]
+
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use reversi_solver::{Game, arena::Arena, solve::{solve_at_depth, solve_at_depth_arena}};
+
+    use crate::common::random_game;
+
+    #[test]
+    fn test_arena_reuse() {
+        let mut arena = Arena::with_capacity(2, &Game::new());
+        assert_eq!(arena.created(), 2);
+
+        let mut game = Game::new();
+        game.play_idx(29).unwrap();
+
+        let copy = arena.get(&game);
+        assert_eq!(copy, game);
+        assert_eq!(arena.available(), 1);
+
+        arena.release(copy);
+        let _ = (arena.get(&game), arena.get(&game), arena.get(&game));
+        assert_eq!(arena.created(), 3);
+    }
+
+    #[test]
+    fn test_solve_with_arena() {
+        let mut rng = fastrand::Rng::with_seed(61);
+        let mut arena = Arena::new();
+
+        for game in random_game(&mut rng).iter().step_by(6) {
+            assert_eq!(solve_at_depth_arena(game, 2, &mut arena), solve_at_depth(game, 2));
+        }
+
+        // the search plays every move on the one game it takes from the pool
+        assert_eq!(arena.created(), 1);
+    }
+}
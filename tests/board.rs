@@ -0,0 +1,783 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::random_game;
+
+    use reversi_solver::{Game, board::{
+        apply_symmetry_to_index, at_pos, get_moves_bitboard, is_c_square, is_corner, is_edge, is_x_square, Board, Cell, CellType, Move, Player, Symmetry,
+        CORNER_MASK, C_SQUARE_MASK, EDGE_MASK, HEIGHT, INNER_MASK, SIZE, WIDTH, X_SQUARE_MASK,
+    }};
+
+    fn set_starting_discs(board: &mut Board) {
+        board.set_cell(3, 3, Cell::Player(Player::One));
+        board.set_cell(4, 4, Cell::Player(Player::One));
+        board.set_cell(3, 4, Cell::Player(Player::Two));
+        board.set_cell(4, 3, Cell::Player(Player::Two));
+    }
+
+    #[test]
+    fn test_array_round_trip() {
+        let game = Game::from_string("--------\n\
+        --------\n\
+        --------\n\
+        ---XO---\n\
+        ---XX---\n\
+        ---X----\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        let mut board = Board::new();
+        for (idx, cell) in game.iter().enumerate() {
+            board.set_cell_idx(idx, cell);
+        }
+
+        let array = board.to_array();
+
+        assert_eq!(array[3][4], Cell::Player(Player::Two));
+        assert_eq!(array[5][3], Cell::Player(Player::One));
+
+        for (y, row) in array.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                assert_eq!(*cell, board.get_cell(x, y));
+            }
+        }
+
+        assert_eq!(array.len(), HEIGHT);
+        assert_eq!(array[0].len(), WIDTH);
+
+        assert_eq!(Board::from_array(array), board);
+    }
+
+    #[test]
+    fn test_connected_components() {
+        let mut board = Board::new();
+
+        set_starting_discs(&mut board);
+
+        // the starting discs only touch diagonally, so every disc is its own component
+        assert_eq!(board.connected_components(Player::One), vec![vec![27], vec![36]]);
+        assert_eq!(board.connected_components(Player::Two), vec![vec![28], vec![35]]);
+
+        let mut line = Board::new();
+        for x in 0..WIDTH {
+            line.set_cell(x, 5, Cell::Player(Player::Two));
+        }
+        line.set_cell(0, 7, Cell::Player(Player::Two));
+
+        assert_eq!(line.connected_components(Player::Two), vec![(40..48).collect::<Vec<_>>(), vec![56]]);
+        assert!(line.connected_components(Player::One).is_empty());
+    }
+
+    #[test]
+    fn test_image_bytes() {
+        let game = Game::new();
+
+        let mut board = Board::new();
+        for (idx, cell) in game.iter().enumerate() {
+            board.set_cell_idx(idx, cell);
+        }
+
+        let cell_size = 20;
+        let bytes = board.to_image_bytes(cell_size, &game.move_indices());
+
+        assert_eq!(bytes.len(), (8 * cell_size) * (8 * cell_size) * 4);
+
+        let pixel = |x: usize, y: usize| {
+            let offset = ((y * cell_size + cell_size / 2) * 8 * cell_size + x * cell_size + cell_size / 2) * 4;
+            &bytes[offset..offset + 4]
+        };
+
+        assert_eq!(pixel(3, 3), &[0, 0, 0, 255]);
+        assert_eq!(pixel(4, 3), &[255, 255, 255, 255]);
+        assert_eq!(pixel(0, 0), &[0, 128, 0, 255]);
+
+        assert!(Board::new().to_image_bytes(0, &[]).is_empty());
+    }
+
+    #[test]
+    fn test_hex() {
+        let mut board = Board::new();
+        set_starting_discs(&mut board);
+
+        assert_eq!(board.fmt_as_hex(), "p1=0x0000001008000000 p2=0x0000000810000000");
+        assert_eq!(Board::from_hex("0x0000001008000000", "0x0000000810000000").unwrap(), board);
+        assert_eq!(Board::from_hex("1008000000", "810000000").unwrap(), board);
+
+        assert_eq!(Board::new().fmt_as_hex(), "p1=0x0000000000000000 p2=0x0000000000000000");
+
+        assert!(Board::from_hex("0x1", "0x3").is_err());
+        assert!(Board::from_hex("0xg", "0x0").is_err());
+        assert!(Board::from_hex("0x10000000000000000", "0x0").is_err());
+    }
+
+    #[test]
+    fn test_iter_empty_and_occupied() {
+        let mut board = Board::new();
+
+        assert_eq!(board.iter_empty().count(), 64);
+        assert_eq!(board.iter_occupied(Player::One).count(), 0);
+
+        set_starting_discs(&mut board);
+
+        assert_eq!(board.iter_empty().count(), 60);
+        assert!(board.iter_empty().all(|idx| board.get_cell_idx(idx) == Cell::Empty));
+        assert_eq!(board.iter_occupied(Player::One).collect::<Vec<_>>(), vec![27, 36]);
+        assert_eq!(board.iter_occupied(Player::Two).collect::<Vec<_>>(), vec![28, 35]);
+    }
+
+    #[test]
+    fn test_board_symmetries() {
+        let mut board = Board::new();
+        board.set_cell(1, 0, Cell::Player(Player::One));
+        board.set_cell(0, 2, Cell::Player(Player::Two));
+        board.set_cell(5, 6, Cell::Player(Player::One));
+
+        let identity = board.rotate_90_clockwise().rotate_90_clockwise().rotate_90_clockwise().rotate_90_clockwise();
+        assert_eq!(identity, board);
+
+        assert_eq!(board.rotate_90_clockwise().rotate_90_clockwise(), board.rotate_180());
+        assert_eq!(board.rotate_180().rotate_90_clockwise(), board.rotate_270_clockwise());
+        assert_eq!(board.mirror_horizontal().mirror_horizontal(), board);
+        assert_eq!(board.mirror_vertical().mirror_vertical(), board);
+        assert_eq!(board.transpose().transpose(), board);
+        assert_eq!(board.mirror_horizontal().mirror_vertical(), board.rotate_180());
+        assert_eq!(board.transpose().mirror_horizontal(), board.rotate_90_clockwise());
+
+        let rotated = board.rotate_90_clockwise();
+        assert_eq!(rotated.get_cell(7, 1), Cell::Player(Player::One));
+        assert_eq!(rotated.get_cell(5, 0), Cell::Player(Player::Two));
+
+        assert_eq!(board.mirror_horizontal().get_cell(6, 0), Cell::Player(Player::One));
+        assert_eq!(board.mirror_vertical().get_cell(1, 7), Cell::Player(Player::One));
+        assert_eq!(board.transpose().get_cell(0, 1), Cell::Player(Player::One));
+    }
+
+    #[test]
+    fn test_population_count() {
+        assert_eq!(Board::new().population_count(), (0, 0));
+
+        let mut rng = fastrand::Rng::with_seed(47);
+        let mut checked = 0;
+
+        while checked < 100 {
+            for game in random_game(&mut rng) {
+                let mut board = Board::new();
+                for (idx, cell) in game.iter().enumerate() {
+                    board.set_cell_idx(idx, cell);
+                }
+
+                let count = |player| board.iter().filter(|cell| **cell == Cell::Player(player)).count() as u32;
+
+                assert_eq!(board.population_count(), (count(Player::One), count(Player::Two)));
+                checked += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_apply_symmetry() {
+        let mut start = Board::new();
+        set_starting_discs(&mut start);
+
+        let mut swapped = Board::new();
+        swapped.set_cell(3, 3, Cell::Player(Player::Two));
+        swapped.set_cell(4, 4, Cell::Player(Player::Two));
+        swapped.set_cell(3, 4, Cell::Player(Player::One));
+        swapped.set_cell(4, 3, Cell::Player(Player::One));
+
+        let expected = [
+            (Symmetry::Identity, &start),
+            (Symmetry::Rot90, &swapped),
+            (Symmetry::Rot180, &start),
+            (Symmetry::Rot270, &swapped),
+            (Symmetry::FlipH, &swapped),
+            (Symmetry::FlipV, &swapped),
+            (Symmetry::FlipDiag, &start),
+            (Symmetry::FlipAntiDiag, &start),
+        ];
+
+        for (sym, board) in expected {
+            assert_eq!(start.apply_symmetry(sym), *board, "{sym:?}");
+        }
+
+        let mut corner = Board::new();
+        corner.set_cell(1, 0, Cell::Player(Player::One));
+
+        for sym in Symmetry::ALL {
+            let transformed = corner.apply_symmetry(sym);
+            assert_eq!(transformed.get_cell_idx(apply_symmetry_to_index(1, sym)), Cell::Player(Player::One));
+            assert_eq!(transformed.apply_symmetry(sym.inverse()), corner);
+        }
+
+        assert_eq!(corner.apply_symmetry(Symmetry::Rot90), corner.rotate_90_clockwise());
+        assert_eq!(corner.apply_symmetry(Symmetry::FlipDiag), corner.transpose());
+    }
+
+    #[test]
+    fn test_cell_types() {
+        let count = |cell_type| (0..64).filter(|idx| CellType::of(*idx) == cell_type).count();
+
+        assert_eq!(count(CellType::Corner), 4);
+        assert_eq!(count(CellType::XSquare), 4);
+        assert_eq!(count(CellType::CSquare), 8);
+        assert_eq!(count(CellType::Edge), 16);
+        assert_eq!(count(CellType::Interior), 32);
+
+        assert_eq!(CellType::of(0), CellType::Corner);
+        assert_eq!(CellType::of(9), CellType::XSquare);
+        assert_eq!(CellType::of(1), CellType::CSquare);
+        assert_eq!(CellType::of(8), CellType::CSquare);
+        assert_eq!(CellType::of(2), CellType::Edge);
+        assert_eq!(CellType::of(10), CellType::Interior);
+        assert_eq!(CellType::of(54), CellType::XSquare);
+    }
+
+    #[test]
+    fn test_full_and_empty() {
+        let mut board = Board::new();
+
+        assert!(board.is_empty_board());
+        assert!(!board.is_full());
+
+        set_starting_discs(&mut board);
+
+        assert!(!board.is_empty_board());
+        assert!(!board.is_full());
+
+        for idx in 0..64 {
+            board.set_cell_idx(idx, Cell::Player(Player::Two));
+        }
+
+        assert!(board.is_full());
+        assert!(!board.is_empty_board());
+
+        assert!(!Game::new().board_is_full());
+    }
+
+    #[test]
+    fn test_apply_move_and_flips() {
+        let mut board = Board::new();
+        set_starting_discs(&mut board);
+
+        // player one plays d6, flipping d5
+        board.apply_move_and_flips(43, 1 << 35, Player::One);
+
+        let mut expected = Board::new();
+        set_starting_discs(&mut expected);
+        expected.set_cell(3, 5, Cell::Player(Player::One));
+        expected.set_cell(3, 4, Cell::Player(Player::One));
+
+        assert_eq!(board, expected);
+
+        board.apply_move_and_flips(0, (1 << 43) | (1 << 63), Player::Two);
+
+        assert_eq!(board.get_cell_idx(0), Cell::Player(Player::Two));
+        assert_eq!(board.get_cell_idx(43), Cell::Player(Player::Two));
+        assert_eq!(board.get_cell_idx(63), Cell::Player(Player::Two));
+        assert_eq!(board.get_cell(3, 3), Cell::Player(Player::One));
+        assert_eq!(board.total_moves(), 7);
+    }
+
+    #[test]
+    fn test_region_masks() {
+        assert_eq!(CORNER_MASK.count_ones(), 4);
+        assert_eq!(EDGE_MASK.count_ones(), 28);
+        assert_eq!(X_SQUARE_MASK.count_ones(), 4);
+        assert_eq!(C_SQUARE_MASK.count_ones(), 8);
+        assert_eq!(INNER_MASK.count_ones(), 36);
+
+        assert_eq!(INNER_MASK, !EDGE_MASK);
+        assert_eq!(Board::inner_squares_mask(), INNER_MASK);
+
+        let mask = |region: fn(usize) -> bool| (0..64).filter(|idx| region(*idx)).fold(0u64, |mask, idx| mask | 1 << idx);
+
+        assert_eq!(CORNER_MASK, mask(is_corner));
+        assert_eq!(EDGE_MASK, mask(is_edge));
+        assert_eq!(X_SQUARE_MASK, mask(is_x_square));
+        assert_eq!(C_SQUARE_MASK, mask(is_c_square));
+    }
+
+    #[test]
+    fn test_fill_masks() {
+        let mut board = Board::new();
+        set_starting_discs(&mut board);
+
+        assert_eq!(board.row_fill_mask(3), 0b0001_1000);
+        assert_eq!(board.col_fill_mask(4), 0b0001_1000);
+        assert_eq!(board.row_fill_mask(0), 0);
+
+        board.set_cell(0, 0, Cell::Player(Player::Two));
+        board.set_cell(7, 0, Cell::Player(Player::One));
+
+        assert_eq!(board.row_fill_mask(0), 0b1000_0001);
+        assert_eq!(board.col_fill_mask(0), 0b0000_0001);
+        assert_eq!(board.col_fill_mask(7), 0b0000_0001);
+    }
+
+    #[test]
+    fn test_accessibility_score() {
+        let mut board = Board::new();
+        assert_eq!(board.accessibility_score(Player::One), 0.0);
+
+        set_starting_discs(&mut board);
+        assert_eq!(board.accessibility_score(Player::One), 1.0);
+
+        // a wall of player two's discs down column c cuts player one off from columns a and b
+        let mut walled = Board::new();
+        for y in 0..HEIGHT {
+            walled.set_cell(2, y, Cell::Player(Player::Two));
+        }
+        walled.set_cell(5, 5, Cell::Player(Player::One));
+
+        assert_eq!(walled.accessibility_score(Player::One), 39.0 / 55.0);
+        assert_eq!(walled.accessibility_score(Player::Two), 1.0);
+
+        // both empty squares border player one's only disc
+        let mut surrounded = Board::new();
+        for idx in 0..64 {
+            surrounded.set_cell_idx(idx, Cell::Player(Player::Two));
+        }
+        surrounded.set_cell(0, 0, Cell::Empty);
+        surrounded.set_cell(1, 0, Cell::Empty);
+        surrounded.set_cell(1, 1, Cell::Player(Player::One));
+
+        assert_eq!(surrounded.accessibility_score(Player::One), 1.0);
+
+        let mut full = Board::new();
+        for idx in 0..64 {
+            full.set_cell_idx(idx, Cell::Player(Player::One));
+        }
+        assert_eq!(full.accessibility_score(Player::One), 0.0);
+
+        let mut rng = fastrand::Rng::with_seed(257);
+
+        for game in random_game(&mut rng) {
+            let mut board = Board::new();
+            for (idx, cell) in game.iter().enumerate() {
+                board.set_cell_idx(idx, cell);
+            }
+
+            for player in [Player::One, Player::Two] {
+                assert!((0.0..=1.0).contains(&board.accessibility_score(player)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_bitboard_round_trip() {
+        let mut start = Board::new();
+        set_starting_discs(&mut start);
+
+        assert_eq!(Board::new().to_bitboards(), (0, 0));
+        assert_eq!(start.to_bitboards(), (1 << 27 | 1 << 36, 1 << 28 | 1 << 35));
+
+        let mut rng = fastrand::Rng::with_seed(61);
+
+        for game in random_game(&mut rng) {
+            let mut board = Board::new();
+            for (idx, cell) in game.iter().enumerate() {
+                board.set_cell_idx(idx, cell);
+            }
+
+            let (player_one, player_two) = board.to_bitboards();
+
+            assert_eq!(player_one & player_two, 0);
+            assert_eq!(Board::from_bitboards(player_one, player_two), board);
+
+            for (idx, (cell, expected)) in board.iter().zip(game.iter()).enumerate() {
+                assert_eq!(*cell, expected);
+                assert_eq!(player_one >> idx & 1 == 1, *cell == Cell::Player(Player::One));
+                assert_eq!(player_two >> idx & 1 == 1, *cell == Cell::Player(Player::Two));
+            }
+        }
+    }
+
+    /// The legal moves of `player` on `board`, found by walking out from every empty cell in
+    /// each direction.
+    fn scalar_moves_mask(board: &Board, player: Player) -> u64 {
+        let mut mask = 0;
+
+        for idx in 0..SIZE {
+            if board.get_cell_idx(idx) != Cell::Empty {
+                continue;
+            }
+
+            let (x, y) = (idx % WIDTH, idx / WIDTH);
+
+            for x_dir in -1..=1 {
+                for y_dir in -1..=1 {
+                    let (mut x, mut y) = (x.wrapping_add_signed(x_dir), y.wrapping_add_signed(y_dir));
+                    let mut run = 0;
+
+                    while board.on_board(x, y) && board.get_cell(x, y) == Cell::Player(player.opponent()) {
+                        run += 1;
+                        (x, y) = (x.wrapping_add_signed(x_dir), y.wrapping_add_signed(y_dir));
+                    }
+
+                    if run > 0 && board.on_board(x, y) && board.get_cell(x, y) == Cell::Player(player) {
+                        mask |= 1 << idx;
+                    }
+                }
+            }
+        }
+
+        mask
+    }
+
+    #[test]
+    fn test_get_moves_bitboard() {
+        let mut start = Board::new();
+        set_starting_discs(&mut start);
+
+        let (player_one, player_two) = start.to_bitboards();
+        let empty = !(player_one | player_two);
+
+        assert_eq!(get_moves_bitboard(player_one, player_two, empty), 1 << 20 | 1 << 29 | 1 << 34 | 1 << 43);
+
+        let mut rng = fastrand::Rng::with_seed(67);
+        let mut checked = 0;
+
+        while checked < 10_000 {
+            for game in random_game(&mut rng) {
+                let mut board = Board::new();
+                for (idx, cell) in game.iter().enumerate() {
+                    board.set_cell_idx(idx, cell);
+                }
+
+                let (player_one, player_two) = board.to_bitboards();
+                let empty = !(player_one | player_two);
+
+                assert_eq!(get_moves_bitboard(player_one, player_two, empty), scalar_moves_mask(&board, Player::One));
+                assert_eq!(get_moves_bitboard(player_two, player_one, empty), scalar_moves_mask(&board, Player::Two));
+
+                assert_eq!(game.legal_moves_mask(), scalar_moves_mask(&board, game.current_player()));
+
+                checked += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_longest_run() {
+        assert_eq!(Board::new().longest_run(Player::One), 0);
+
+        let mut full_row = Board::new();
+        for x in 0..WIDTH {
+            full_row.set_cell(x, 5, Cell::Player(Player::One));
+        }
+
+        assert_eq!(full_row.longest_run(Player::One), 8);
+        assert_eq!(full_row.longest_run(Player::Two), 0);
+
+        let mut single = Board::new();
+        single.set_cell(2, 6, Cell::Player(Player::Two));
+
+        assert_eq!(single.longest_run(Player::Two), 1);
+
+        // alternating discs along every other row, with empty rows between them, so no two
+        // discs of the same player touch in any direction
+        let mut alternating = Board::new();
+        for y in (0..HEIGHT).step_by(2) {
+            for x in (0..WIDTH).step_by(2) {
+                alternating.set_cell(x, y, Cell::Player(Player::One));
+                alternating.set_cell(x + 1, y, Cell::Player(Player::Two));
+            }
+        }
+
+        assert_eq!(alternating.longest_run(Player::One), 1);
+        assert_eq!(alternating.longest_run(Player::Two), 1);
+
+        // the anti-diagonal from the top right corner
+        let mut diagonal = Board::new();
+        for step in 0..5 {
+            diagonal.set_cell(WIDTH - 1 - step, step, Cell::Player(Player::One));
+        }
+
+        assert_eq!(diagonal.longest_run(Player::One), 5);
+    }
+
+    #[test]
+    fn test_move() {
+        let mv = Move::new(3, 2);
+
+        assert_eq!((mv.x(), mv.y(), mv.index()), (3, 2, 19));
+        assert_eq!(mv, Move::from_index(19));
+        assert_eq!(mv.to_coord(), "d3");
+        assert_eq!(Move::from_coord("d3").unwrap(), mv);
+
+        assert_eq!(Move::new(0, 0).to_coord(), "a1");
+        assert_eq!(Move::new(7, 7).to_coord(), "h8");
+
+        assert!(Move::from_coord("i1").is_err());
+        assert!(Move::from_coord("a9").is_err());
+        assert!(Move::from_coord("a").is_err());
+
+        let mut game = Game::new();
+        let first = game.moves()[0];
+
+        assert_eq!(game.moves().iter().map(Move::index).collect::<Vec<_>>(), game.move_indices());
+
+        let mut by_index = game.clone();
+        by_index.play_idx(first.index()).unwrap();
+        game.play_move(first).unwrap();
+
+        assert_eq!(game, by_index);
+    }
+
+    #[test]
+    fn test_coord_round_trip() {
+        let columns = ["a", "b", "c", "d", "e", "f", "g", "h"];
+
+        for y in 0..HEIGHT {
+            for (x, column) in columns.iter().enumerate() {
+                let mv = Move::new(x, y);
+                let coord = format!("{}{}", column, y + 1);
+
+                assert_eq!(mv.to_coord(), coord);
+                assert_eq!(Move::from_coord(&coord).unwrap(), mv);
+                assert_eq!(Move::from_coord(&coord.to_uppercase()).unwrap(), mv);
+                assert_eq!(Move::from_coord(&mv.to_coord()).unwrap(), mv);
+            }
+        }
+
+        assert_eq!(Move::from_coord("D3").unwrap(), Move::new(3, 2));
+
+        for coord in ["", "a", "a0", "a9", "i1", "I1", "d3 ", "d33", "3d", "@1"] {
+            assert!(Move::from_coord(coord).is_err(), "{coord}");
+        }
+
+        assert!(Move::from_coord("j4").unwrap_err().to_string().contains("column"));
+        assert!(Move::from_coord("c0").unwrap_err().to_string().contains("row"));
+
+        let mut game = Game::new();
+        let mut expected = game.clone();
+
+        game.play_coord("E3").unwrap();
+        expected.play_move(Move::new(4, 2)).unwrap();
+        assert_eq!(game, expected);
+
+        assert!(game.play_coord("a1").is_err());
+        assert!(game.play_coord("z9").is_err());
+    }
+
+    #[test]
+    fn test_count_adjacency() {
+        let mut board = Board::new();
+        set_starting_discs(&mut board);
+
+        // each centre disc touches the other three
+        for (x, y) in [(3, 3), (4, 3), (3, 4), (4, 4)] {
+            assert_eq!(board.count_adjacency(x, y), 3);
+        }
+
+        assert_eq!(board.count_adjacency_by_player(3, 3, Player::One), 1);
+        assert_eq!(board.count_adjacency_by_player(3, 3, Player::Two), 2);
+
+        // c3 only touches d4, e3 touches d4 and e4, and the corners are far away
+        assert_eq!(board.count_adjacency(2, 2), 1);
+        assert_eq!(board.count_adjacency(4, 2), 2);
+        assert_eq!(board.count_adjacency_by_player(4, 2, Player::Two), 1);
+        assert_eq!(board.count_adjacency(0, 0), 0);
+
+        let mut full = Board::new();
+        for idx in 0..SIZE {
+            full.set_cell_idx(idx, Cell::Player(Player::Two));
+        }
+
+        assert_eq!(full.count_adjacency(0, 0), 3);
+        assert_eq!(full.count_adjacency(0, 3), 5);
+        assert_eq!(full.count_adjacency_by_player(3, 3, Player::Two), 8);
+        assert_eq!(full.count_adjacency_by_player(3, 3, Player::One), 0);
+    }
+
+    #[test]
+    fn test_stability_masks() {
+        let mut board = Board::new();
+        set_starting_discs(&mut board);
+
+        assert_eq!(board.stability_mask(Player::One), 0);
+        assert_eq!(board.weak_stability_mask(Player::One), 0);
+
+        // a corner and the edge run from it are stable, but none of their lines are full
+        board.set_cell(0, 0, Cell::Player(Player::One));
+        board.set_cell(1, 0, Cell::Player(Player::One));
+        board.set_cell(0, 1, Cell::Player(Player::One));
+
+        assert_eq!(board.stability_mask(Player::One), 1 << 0 | 1 << 1 | 1 << 8);
+        assert_eq!(board.weak_stability_mask(Player::One), 0);
+
+        let mut full = Board::new();
+        for idx in 0..SIZE {
+            full.set_cell_idx(idx, Cell::Player(if idx % 3 == 0 { Player::One } else { Player::Two }));
+        }
+
+        assert_eq!(full.weak_stability_mask(Player::One), full.to_bitboards().0);
+        assert_eq!(full.stability_mask(Player::Two), full.to_bitboards().1);
+
+        let mut rng = fastrand::Rng::with_seed(101);
+
+        for _ in 0..5 {
+            for game in random_game(&mut rng) {
+                let (player_one, player_two) = game.board().to_bitboards();
+
+                for (player, own) in [(Player::One, player_one), (Player::Two, player_two)] {
+                    let weak = game.board().weak_stability_mask(player);
+                    let stable = game.board().stability_mask(player);
+
+                    assert_eq!(weak & !stable, 0);
+                    assert_eq!(stable & !own, 0);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_game_iter_with_pos() {
+        let mut rng = fastrand::Rng::with_seed(269);
+
+        for game in random_game(&mut rng).iter().step_by(5) {
+            assert_eq!(game.iter().count(), SIZE);
+
+            for ((x, y, cell), (idx, expected)) in game.iter_with_pos().zip(game.iter().enumerate()) {
+                assert_eq!(at_pos(x, y), idx);
+                assert_eq!(cell, expected);
+                assert_eq!(cell, game.board().get_cell(x, y));
+            }
+        }
+    }
+
+    #[test]
+    fn test_board_into_iterator() {
+        let mut rng = fastrand::Rng::with_seed(270);
+
+        for game in random_game(&mut rng).iter().step_by(5) {
+            let board = game.board();
+
+            let mut count = 0;
+            for (idx, cell) in board.into_iter().enumerate() {
+                assert_eq!(*cell, board.get_cell_idx(idx));
+                count += 1;
+            }
+            assert_eq!(count, SIZE);
+
+            assert_eq!(board.iter().len(), SIZE);
+            assert!(board.into_iter().eq(board.iter()));
+        }
+    }
+
+    #[test]
+    fn test_board_bytes() {
+        let mut rng = fastrand::Rng::with_seed(274);
+
+        for _ in 0..1000 {
+            let (player_one, player_two) = (rng.u64(..), rng.u64(..));
+            let board = Board::from_bitboards(player_one, player_two);
+            let bytes = board.to_bytes();
+
+            assert_eq!(&bytes[..8], &player_one.to_le_bytes());
+            assert_eq!(Board::from_bytes(&bytes).unwrap(), board);
+
+            // a random pair of bitboards almost always overlaps
+            let mut overlapping = [0; 16];
+            overlapping[..8].copy_from_slice(&player_one.to_le_bytes());
+            overlapping[8..].copy_from_slice(&player_two.to_le_bytes());
+            assert_eq!(Board::from_bytes(&overlapping).is_err(), player_one & player_two != 0);
+        }
+
+        for game in random_game(&mut rng) {
+            assert_eq!(Board::from_bytes(&game.board().to_bytes()).unwrap(), *game.board());
+        }
+
+        let mut one_cell = [0; 16];
+        one_cell[3] = 0b100;
+        one_cell[11] = 0b100;
+        assert!(Board::from_bytes(&one_cell).is_err());
+
+        one_cell[11] = 0b1000;
+        assert_eq!(Board::from_bytes(&one_cell).unwrap().get_cell_idx(26), Cell::Player(Player::One));
+        assert_eq!(Board::from_bytes(&one_cell).unwrap().get_cell_idx(27), Cell::Player(Player::Two));
+        assert_eq!(Board::from_bytes(&[0; 16]).unwrap(), Board::new());
+    }
+
+    #[test]
+    fn test_board_try_from_chars() {
+        let mut chars = [['-'; WIDTH]; HEIGHT];
+        chars[3][3] = 'X';
+        chars[3][4] = 'O';
+        chars[4][3] = 'O';
+        chars[4][4] = 'X';
+
+        assert_eq!(Board::try_from(chars).unwrap(), *Game::new().board());
+
+        let mut rng = fastrand::Rng::with_seed(275);
+
+        for game in random_game(&mut rng) {
+            let chars = game.board().to_array().map(|row| row.map(|cell| cell.to_char()));
+            let board = Board::try_from(chars).unwrap();
+
+            assert_eq!(board, *game.board());
+            assert_eq!(Board::from_array(board.to_array()), board);
+        }
+
+        // cells are read as by `Cell::try_from`, so empty squares may also be `.` or `*`
+        assert!(Board::try_from([['.'; WIDTH]; HEIGHT]).unwrap().is_empty_board());
+        assert!(Board::try_from([['*'; WIDTH]; HEIGHT]).unwrap().is_empty_board());
+
+        for invalid in ['x', 'o', ' ', '0'] {
+            let mut chars = [['-'; WIDTH]; HEIGHT];
+            chars[2][5] = invalid;
+
+            let err = Board::try_from(chars).unwrap_err().to_string();
+            assert!(err.contains("f3"), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_try_from_char() {
+        for character in (0..=0x7f).map(char::from).chain(['×', '●', '○']) {
+            let expected_cell = match character {
+                'X' => Some(Cell::Player(Player::One)),
+                'O' => Some(Cell::Player(Player::Two)),
+                '-' | '.' | '*' => Some(Cell::Empty),
+                _ => None,
+            };
+            assert_eq!(Cell::try_from(character).ok(), expected_cell, "{character:?}");
+
+            let expected_player = match character {
+                'X' | 'x' => Some(Player::One),
+                'O' | 'o' => Some(Player::Two),
+                _ => None,
+            };
+            assert_eq!(Player::try_from(character).ok(), expected_player, "{character:?}");
+        }
+
+        for player in [Player::One, Player::Two] {
+            assert_eq!(Player::try_from(player.to_char()).unwrap(), player);
+            assert_eq!(Cell::try_from(player.to_char()).unwrap(), Cell::Player(player));
+            assert_eq!(player.to_char(), Cell::Player(player).to_char());
+        }
+
+        assert_eq!(Cell::try_from(Cell::Empty.to_char()).unwrap(), Cell::Empty);
+
+        // `from_string` also reads `.` as empty
+        let dotted = Game::new().to_string().lines().skip(1).map(|row| row.replace(['-', '*'], ".")).collect::<Vec<_>>().join("\n");
+        assert_eq!(Game::from_string(&dotted, Player::One, false).unwrap(), Game::new());
+        assert!(Game::from_string("x-------", Player::One, false).is_err());
+    }
+
+    #[test]
+    fn test_display_player_and_cell() {
+        assert_eq!(Player::One.to_string(), "X");
+        assert_eq!(Player::Two.to_string(), "O");
+        assert_eq!(Cell::Player(Player::One).to_string(), "X");
+        assert_eq!(Cell::Player(Player::Two).to_string(), "O");
+        assert_eq!(Cell::Empty.to_string(), "-");
+
+        assert_eq!(format!("{:>3}|{:<2}|", Cell::Empty, Player::Two), "  -|O |");
+
+        for cell in [Cell::Empty, Cell::Player(Player::One), Cell::Player(Player::Two)] {
+            assert_eq!(cell.to_string(), cell.to_char().to_string());
+        }
+    }
+}
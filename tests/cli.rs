@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    fn run(args: &[&str]) -> String {
+        let output = Command::new(env!("CARGO_BIN_EXE_reversi-solver")).args(args).output().unwrap();
+
+        assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+
+        String::from_utf8(output.stdout).unwrap()
+    }
+
+    #[test]
+    fn test_stats() {
+        for args in [&["stats", "--games", "5"][..], &["stats", "--games", "2", "--depth", "1"]] {
+            let output = run(args);
+            let lines = output.lines().collect::<Vec<_>>();
+
+            assert_eq!(lines.len(), 5, "{output}");
+            assert_eq!(lines[0], format!("Games: {}", args[2]));
+            assert!(lines[1].starts_with("Average length: ") && lines[1].ends_with(" moves"));
+            assert!(lines[2].starts_with("Average disc differential (X - O): "));
+            assert!(lines[3].starts_with("First player win rate: ") && lines[3].ends_with('%'));
+            assert!(lines[4].starts_with("Average passes: "));
+
+            let length = lines[1]["Average length: ".len()..lines[1].len() - " moves".len()].parse::<f64>().unwrap();
+            assert!((1.0..=60.0).contains(&length));
+        }
+
+        let output = Command::new(env!("CARGO_BIN_EXE_reversi-solver")).args(["stats", "--games", "0"]).output().unwrap();
+        assert!(!output.status.success());
+    }
+
+    #[test]
+    fn test_solve_time() {
+        // the position has few enough empty squares for the timed search to reach the end well
+        // within the limit, so both print the same exact scores
+        let exact = run(&["solve"]);
+
+        assert_eq!(run(&["solve", "--time", "10000"]), exact);
+        assert_eq!(exact.lines().nth(3), Some(" 12  -  O  O  O  O  O  O"));
+    }
+
+    #[test]
+    fn test_save_load() {
+        let path = std::env::temp_dir().join(format!("reversi-solver-save-{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let saved = run(&["save", path, "e3", "d3", "c3"]);
+        let loaded = run(&["load", path]);
+
+        assert!(loaded.starts_with(&saved), "{loaded}");
+        assert!(loaded.ends_with("1. (Black) e3\n1. (White) d3\n2. (Black) c3\n"), "{loaded}");
+        assert!(run(&["import", "--format", "json", path]).starts_with("Current player: O\n"));
+
+        std::fs::write(path, &std::fs::read_to_string(path).unwrap()[..20]).unwrap();
+        let output = Command::new(env!("CARGO_BIN_EXE_reversi-solver")).args(["load", path]).output().unwrap();
+        assert!(!output.status.success());
+
+        let output = Command::new(env!("CARGO_BIN_EXE_reversi-solver")).args(["save", path, "a1"]).output().unwrap();
+        assert!(!output.status.success());
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,54 @@
+#![allow(dead_code)]
+
+use reversi_solver::{Game, record::GameRecord};
+
+/// Plays a random game to completion, returning every position reached along the way
+/// (starting with the initial position).
+pub fn random_game(rng: &mut fastrand::Rng) -> Vec<Game> {
+    let mut game = Game::new();
+    let mut positions = vec![game.clone()];
+
+    loop {
+        let mut moves = game.moves();
+
+        if moves.is_empty() {
+            game.pass().unwrap();
+            moves = game.moves();
+
+            if moves.is_empty() {
+                break;
+            }
+        }
+
+        game.play_move(moves[rng.usize(..moves.len())]).unwrap();
+        positions.push(game.clone());
+    }
+
+    positions
+}
+
+/// Plays a random game to completion, recording its moves and passes.
+pub fn random_record(rng: &mut fastrand::Rng) -> GameRecord {
+    let mut game = Game::new();
+    let mut moves = Vec::new();
+
+    loop {
+        let possible_moves = game.moves();
+
+        if possible_moves.is_empty() {
+            if game.is_game_over() {
+                break;
+            }
+
+            game.pass().unwrap();
+            moves.push(None);
+            continue;
+        }
+
+        let chosen_move = possible_moves[rng.usize(..possible_moves.len())];
+        game.play_move(chosen_move).unwrap();
+        moves.push(Some(chosen_move.index()));
+    }
+
+    GameRecord::new(moves)
+}
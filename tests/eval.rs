@@ -0,0 +1,96 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use reversi_solver::{Game, board::{Cell, Player, CORNER_MASK, C_SQUARE_MASK, EDGE_MASK, SIZE, X_SQUARE_MASK}, eval::{DiscDifferenceEval, EvaluationFn, MobilityEval, WeightedDiscEval}, solve::{disc_difference, negamax_depth}};
+
+    use crate::common::random_game;
+
+    fn evaluators() -> [&'static dyn EvaluationFn; 3] {
+        [&DiscDifferenceEval, &WeightedDiscEval, &MobilityEval]
+    }
+
+    #[test]
+    fn test_initial_position() {
+        // the starting position is symmetric between the players
+        for eval in evaluators() {
+            assert_eq!(eval.evaluate(&Game::new()), 0);
+        }
+
+        // e3 flips e4, leaving X four discs against O's d5
+        let mut game = Game::new();
+        game.play_idx(20).unwrap();
+
+        assert_eq!(DiscDifferenceEval.evaluate(&game), -3);
+        assert_eq!(WeightedDiscEval.evaluate(&game), 5 - (4 + 5 + 5 + 5));
+        assert_eq!(MobilityEval.evaluate(&game), 3 - 3);
+    }
+
+    #[test]
+    fn test_evaluators() {
+        let mut rng = fastrand::Rng::with_seed(278);
+
+        for game in random_game(&mut rng) {
+            let mut swapped = game.clone();
+            #[allow(deprecated)]
+            swapped.swap_players();
+
+            assert_eq!(DiscDifferenceEval.evaluate(&game), disc_difference(&game));
+            assert_eq!(MobilityEval.evaluate(&game), game.move_indices().len() as isize - swapped.move_indices().len() as isize);
+
+            let weights = game.iter().zip(WeightedDiscEval::WEIGHTS).map(|(cell, weight)| match cell {
+                Cell::Player(Player::One) => weight,
+                Cell::Player(Player::Two) => -weight,
+                Cell::Empty => 0,
+            }).sum::<isize>();
+            let expected = if game.current_player() == Player::One { weights } else { -weights };
+            assert_eq!(WeightedDiscEval.evaluate(&game), expected);
+
+            for eval in evaluators() {
+                assert_eq!(eval.evaluate(&game), -eval.evaluate(&swapped));
+
+                if game.terminal_score().is_none() {
+                    assert_eq!(negamax_depth(&game, 0, -(SIZE as isize), SIZE as isize, eval).unwrap(), eval.evaluate(&game));
+                }
+            }
+        }
+
+        // plain functions are evaluators too
+        let game = Game::new();
+        assert_eq!(negamax_depth(&game, 2, -(SIZE as isize), SIZE as isize, &disc_difference).unwrap(), negamax_depth(&game, 2, -(SIZE as isize), SIZE as isize, &DiscDifferenceEval).unwrap());
+    }
+
+    #[test]
+    fn test_weighted_disc_eval() {
+        let only = |mask: u64, player: Player| {
+            let rows = (0..8).map(|y| (0..8).map(|x| if mask >> (8 * y + x) & 1 == 1 { 'X' } else { '-' }).collect::<String>()).collect::<Vec<_>>();
+            Game::from_string(&rows.join("\n"), player, false).unwrap()
+        };
+
+        assert_eq!(WeightedDiscEval.evaluate(&only(CORNER_MASK, Player::One)), 4 * 120);
+        assert_eq!(WeightedDiscEval.evaluate(&only(X_SQUARE_MASK, Player::One)), 4 * -20);
+        assert_eq!(WeightedDiscEval.evaluate(&only(C_SQUARE_MASK, Player::One)), 8 * -20);
+
+        // the same discs are scored from O's side when O is to move
+        assert_eq!(WeightedDiscEval.evaluate(&only(CORNER_MASK, Player::Two)), -4 * 120);
+        assert_eq!(WeightedDiscEval.evaluate(&only(X_SQUARE_MASK, Player::Two)), 4 * 20);
+
+        for (idx, weight) in WeightedDiscEval::WEIGHTS.into_iter().enumerate() {
+            let bit = 1 << idx;
+
+            let expected = if CORNER_MASK & bit != 0 {
+                120
+            } else if (X_SQUARE_MASK | C_SQUARE_MASK) & bit != 0 {
+                -20
+            } else if EDGE_MASK & bit != 0 {
+                20
+            } else {
+                assert!((3..=5).contains(&weight), "{idx}");
+                weight
+            };
+
+            assert_eq!(weight, expected, "{idx}");
+            assert_eq!(weight, WeightedDiscEval::WEIGHTS[SIZE - 1 - idx]);
+        }
+    }
+}
@@ -1,8 +1,12 @@
+mod common;
+
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
+    use std::{collections::HashMap, path::PathBuf};
+
+    use crate::common::{random_game, random_record};
 
-    use reversi_solver::{Game, board::Player};
+    use reversi_solver::{EvalWeights, Game, GameResult, ReversiError, board::{Board, Cell, CellType, Move, Player, Symmetry, SIZE}, book::{OpeningBook, BOOK_SCORE}, record::{GameMetadata, GameRecord}, solve::{self, SolverStrategy}};
 
     #[test]
     fn test_games() {
@@ -44,4 +48,1843 @@ mod tests {
             }
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_valid_sequence() {
+        let game = Game::new();
+
+        assert!(game.is_valid_sequence(&[]));
+        assert!(game.is_valid_sequence(&[29, 37]));
+
+        assert!(!game.is_valid_sequence(&[0]));
+        assert!(!game.is_valid_sequence(&[29, 29]));
+        assert!(!game.is_valid_sequence(&[64]));
+
+        // the original game is left untouched
+        assert_eq!(game, Game::new());
+    }
+
+    #[test]
+    fn test_expected_remaining_moves() {
+        assert_eq!(Game::new().expected_remaining_moves(), 60.0);
+
+        let mut rng = fastrand::Rng::with_seed(7);
+        let mut totals = [0.0; 7];
+
+        for _ in 0..50 {
+            let positions = random_game(&mut rng);
+
+            let last = positions.last().unwrap();
+            assert_eq!(last.expected_remaining_moves(), 0.0);
+
+            for position in &positions {
+                let empty_squares = (64 - position.total_moves()) as f64;
+                assert!(position.expected_remaining_moves() <= empty_squares);
+            }
+
+            for (i, total) in totals.iter_mut().enumerate() {
+                *total += positions.get(i * 10).map_or(0.0, |game| game.expected_remaining_moves());
+            }
+        }
+
+        for window in totals.windows(2) {
+            assert!(window[0] > window[1], "{totals:?}");
+        }
+    }
+
+    #[test]
+    fn test_terminal_score() {
+        assert_eq!(Game::new().terminal_score(), None);
+
+        let win = Game::from_string("X-------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        // the empty squares are counted towards the winner
+        assert_eq!(win.terminal_score(), Some(64));
+
+        let loss = Game::from_string("X-------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        ------OO", Player::One, false).unwrap();
+
+        assert_eq!(loss.terminal_score(), Some(-62));
+
+        let draw = Game::from_string("XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        OOOOOOOO\n\
+        OOOOOOOO\n\
+        OOOOOOOO\n\
+        OOOOOOOO", Player::One, false).unwrap();
+
+        assert_eq!(draw.terminal_score(), Some(0));
+    }
+
+    #[test]
+    fn test_symmetric_positions() {
+        let mut rng = fastrand::Rng::with_seed(3);
+        let positions = random_game(&mut rng);
+
+        for game in positions.iter().step_by(7) {
+            let symmetries = game.make_symmetric_positions();
+
+            assert_eq!(symmetries[0], *game);
+
+            let mut expected = symmetries.iter().map(|game| format!("{game:?}")).collect::<Vec<_>>();
+            expected.sort();
+
+            for symmetry in &symmetries {
+                assert_eq!(symmetry.moves().len(), game.moves().len());
+                assert_eq!(symmetry.total_moves(), game.total_moves());
+
+                // the symmetries form a group, so each one reaches every other
+                let mut reached = symmetry.make_symmetric_positions().iter().map(|game| format!("{game:?}")).collect::<Vec<_>>();
+                reached.sort();
+
+                assert_eq!(reached, expected);
+            }
+
+            // four quarter turns are the identity
+            let mut rotated = game.clone();
+            for _ in 0..4 {
+                rotated = rotated.make_symmetric_positions()[1].clone();
+            }
+            assert_eq!(rotated, *game);
+        }
+    }
+
+    #[test]
+    fn test_score_overlay() {
+        let game = Game::new();
+
+        // with no scores, the overlay is the plain board and marks the legal moves
+        let board = "Current player: X\n\
+        --------\n\
+        --------\n\
+        ----*---\n\
+        ---XO*--\n\
+        --*OX---\n\
+        ---*----\n\
+        --------\n\
+        --------\n";
+
+        assert_eq!(game.score_overlay_string(&HashMap::new()), board);
+        assert_eq!(game.to_string(), board);
+
+        let scores = game.move_indices().into_iter().map(|idx| (idx, (idx % 5) as isize - 2)).collect::<HashMap<_, _>>();
+        let overlay = game.score_overlay_string(&scores);
+        let lines = overlay.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "Current player: X");
+        assert_eq!(lines.len(), 9);
+        assert!(!overlay.contains('*'));
+
+        assert_eq!(lines[3], "  -  -  -  - -2  -  -  -");
+        assert_eq!(lines[4], "  -  -  -  X  O  2  -  -");
+        assert_eq!(lines[5], "  -  -  2  O  X  -  -  -");
+        assert_eq!(lines[6], "  -  -  -  1  -  -  -  -");
+    }
+
+    #[test]
+    fn test_training_sample() {
+        let sample = Game::new().to_training_sample(2);
+
+        assert_eq!(sample.features.len(), 128);
+        assert_eq!(sample.policy.len(), 64);
+
+        assert_eq!(sample.features.iter().sum::<f32>(), 4.0);
+        assert_eq!(&sample.features[54..58], &[1.0, 0.0, 0.0, 1.0]);
+
+        assert!((-1.0..=1.0).contains(&sample.value));
+        assert!((sample.policy.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+
+        for (idx, probability) in sample.policy.iter().enumerate() {
+            if ![20, 29, 34, 43].contains(&idx) {
+                assert_eq!(*probability, 0.0);
+            }
+        }
+
+        let mut rng = fastrand::Rng::with_seed(11);
+        let end = random_game(&mut rng).pop().unwrap();
+        let sample = end.to_training_sample(2);
+
+        assert!((-1.0..=1.0).contains(&sample.value));
+        assert!(sample.policy.iter().all(|probability| *probability == 0.0));
+    }
+
+    #[test]
+    fn test_import_formats() {
+        assert_eq!(Game::from_fen("8/8/8/3XO3/3OX3/8/8/8 X").unwrap(), Game::new());
+        assert!(Game::from_fen("8/8/8/3XO3/3OX3/8/8 X").is_err());
+        assert!(Game::from_fen("8/8/8/3XO4/3OX3/8/8/8 X").is_err());
+        assert!(Game::from_fen("8/8/8/3XO3/3OX3/8/8/8").is_err());
+
+        let mut rng = fastrand::Rng::with_seed(5);
+        for game in random_game(&mut rng) {
+            assert_eq!(Game::from_compressed_string(&format!("{game:?}")).unwrap(), game);
+        }
+        assert!(Game::from_compressed_string("X:---").is_err());
+        assert!(Game::from_compressed_string("").is_err());
+
+        let mut expected = Game::new();
+        expected.play_idx(29).unwrap();
+        expected.play_idx(37).unwrap();
+
+        assert_eq!(Game::from_sgf("(;GM[2]FF[4];B[f4];W[f5])").unwrap(), expected);
+        assert!(Game::from_sgf("(;GM[2];B[a1])").is_err());
+        assert!(Game::from_sgf("(;GM[2];W[f5])").is_err());
+    }
+
+    #[test]
+    fn test_alpha_beta_window() {
+        let mut rng = fastrand::Rng::with_seed(13);
+
+        for _ in 0..5 {
+            let positions = random_game(&mut rng);
+
+            for game in positions.iter().filter(|game| (5..=8).contains(&(64 - game.total_moves()))) {
+                let exact = solve::negamax_depth(game, 64, -(SIZE as isize), SIZE as isize, &solve::disc_difference).unwrap();
+                let (score, moves) = game.alpha_beta_window(-65, 65);
+
+                assert_eq!(score, exact);
+
+                if !game.moves().is_empty() {
+                    assert!(!moves.is_empty());
+                    assert_eq!(solve::solve_at_depth(game, 64).iter().find(|(_, idx)| *idx == moves[0]).unwrap().0, exact);
+                }
+
+                // a window that excludes the score is a non-PV node
+                let (bound, moves) = game.alpha_beta_window(exact, exact + 10);
+                assert!(bound <= exact);
+                assert!(moves.is_empty());
+            }
+        }
+
+        // X wipes O out by taking c1, which only a window wider than the score can show
+        let wipe_out = Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        assert_eq!(wipe_out.alpha_beta_window(-65, 65), (64, vec![2]));
+        assert_eq!(wipe_out.alpha_beta_window(-64, 64), (64, Vec::new()));
+
+        // and O, left without a disc, has lost by the whole board
+        let mut lost = wipe_out.clone();
+        lost.play_idx(2).unwrap();
+        assert_eq!(lost.alpha_beta_window(-65, 65), (-64, Vec::new()));
+    }
+
+    #[test]
+    fn test_san() {
+        for (idx, san) in [(0, "a1"), (7, "h1"), (56, "a8"), (63, "h8"), (27, "d4"), (28, "e4"), (35, "d5"), (36, "e5")] {
+            assert_eq!(Game::move_to_san(idx), san);
+            assert_eq!(Game::san_to_move(san).unwrap(), idx);
+        }
+
+        for idx in 0..64 {
+            assert_eq!(Game::san_to_move(&Game::move_to_san(idx)).unwrap(), idx);
+        }
+
+        for san in ["", "a", "a0", "a9", "i1", "d3 ", "d33", "3d"] {
+            assert!(Game::san_to_move(san).is_err(), "{san}");
+        }
+    }
+
+    #[test]
+    fn test_ply_notation() {
+        for (ply, notation) in [(0, "1. (Black)"), (1, "1. (White)"), (2, "2. (Black)"), (9, "5. (White)"), (59, "30. (White)"), (60, "31. (Black)")] {
+            assert_eq!(Game::ply_to_notation(ply), notation);
+            assert_eq!(Game::notation_to_ply(notation).unwrap(), ply);
+        }
+
+        for ply in 0..200 {
+            assert_eq!(Game::notation_to_ply(&Game::ply_to_notation(ply)).unwrap(), ply);
+        }
+
+        for notation in ["", "1.", "1. ", "0. (Black)", "-1. (Black)", "1 (Black)", "1. Black", "1. (black)", "1. (Red)", "x. (White)"] {
+            assert!(Game::notation_to_ply(notation).is_err(), "{notation}");
+        }
+
+        let record = GameRecord::new(vec![Some(20), Some(19), None]);
+        assert_eq!(record.to_string(), "1. (Black) e3\n1. (White) d3\n2. (Black) --\n");
+        assert_eq!(GameRecord::default().to_string(), "");
+    }
+
+    #[test]
+    fn test_top_k_moves() {
+        let game = Game::new();
+
+        assert_eq!(game.top_k_moves(10, 1).len(), 4);
+        assert_eq!(game.top_k_moves(2, 1).len(), 2);
+        assert!(game.top_k_moves(0, 1).is_empty());
+
+        let mut rng = fastrand::Rng::with_seed(17);
+
+        for _ in 0..10 {
+            let positions = random_game(&mut rng);
+
+            for game in positions.iter().filter(|game| (1..=3).contains(&(64 - game.total_moves()))) {
+                let (exact, best_moves) = game.alpha_beta_window(-65, 65);
+
+                if best_moves.is_empty() {
+                    continue;
+                }
+
+                let top = game.top_k_moves(1, 3);
+                let mut after_top = game.clone();
+                after_top.play_idx(top[0]).unwrap();
+
+                assert_eq!(-solve::negamax_depth(&after_top, 64, -(SIZE as isize), SIZE as isize, &solve::disc_difference).unwrap(), exact);
+            }
+        }
+    }
+
+    #[test]
+    fn test_eval_with_weights() {
+        let mut rng = fastrand::Rng::with_seed(23);
+
+        for game in random_game(&mut rng) {
+            assert_eq!(game.eval_with_weights(&Default::default()), game.evaluate());
+        }
+
+        // the starting position is balanced apart from parity
+        assert_eq!(Game::new().evaluate(), -2);
+
+        let only_discs = EvalWeights { disc_count: 1.0, mobility: 0.0, corners: 0.0, stability: 0.0, frontier: 0.0, parity: 0.0 };
+
+        let corner = Game::from_string("XXX-----\n\
+        X-------\n\
+        --------\n\
+        ---XO---\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        assert_eq!(corner.eval_with_weights(&only_discs), 4);
+
+        let only_corners = EvalWeights { disc_count: 0.0, corners: 1.0, ..only_discs };
+        assert_eq!(corner.eval_with_weights(&only_corners), 1);
+
+        let only_stability = EvalWeights { disc_count: 0.0, stability: 1.0, ..only_discs };
+        assert_eq!(corner.eval_with_weights(&only_stability), 4);
+
+        let mut opponent = corner.clone();
+        #[allow(deprecated)]
+        opponent.swap_players();
+        assert_eq!(opponent.eval_with_weights(&only_stability), -4);
+    }
+
+    #[test]
+    fn test_tune_weights() {
+        let mut rng = fastrand::Rng::with_seed(29);
+        let records = (0..20).map(|_| random_record(&mut rng)).collect::<Vec<_>>();
+
+        let loss = |weights: &EvalWeights| {
+            let mut total = 0.0;
+
+            for record in &records {
+                let positions = record.replay().unwrap();
+                let final_score = positions.last().unwrap().terminal_score().unwrap();
+
+                for game in &positions {
+                    let player_one_to_move = game.current_player() == Player::One;
+
+                    let score = if player_one_to_move { final_score } else { -final_score };
+                    let label = (score.signum() as f32 + 1.0) / 2.0;
+
+                    let prediction = (1.0 / (1.0 + (-(game.eval_with_weights(weights) as f32) / 16.0).exp())).clamp(1e-6, 1.0 - 1e-6);
+                    total -= label * prediction.ln() + (1.0 - label) * (1.0 - prediction).ln();
+                }
+            }
+
+            total
+        };
+
+        let tuned = Game::tune_weights(&records);
+
+        assert_ne!(tuned, EvalWeights::default());
+        assert!(loss(&tuned) < loss(&EvalWeights::default()));
+
+        assert_eq!(Game::tune_weights(&[]), EvalWeights::default());
+    }
+
+    #[test]
+    fn test_map_move_symmetry() {
+        for sym in Symmetry::ALL {
+            assert_eq!(sym.inverse().inverse(), sym);
+
+            for mv in 0..64 {
+                assert_eq!(Game::map_move_to_original(Game::map_move_to_symmetric(mv, sym), sym), mv);
+                assert_eq!(Game::map_move_to_symmetric(Game::map_move_to_original(mv, sym), sym), mv);
+            }
+        }
+
+        assert_eq!(Game::map_move_to_symmetric(0, Symmetry::Rot90), 7);
+        assert_eq!(Game::map_move_to_original(7, Symmetry::Rot90), 0);
+
+        let mut rng = fastrand::Rng::with_seed(31);
+        let positions = random_game(&mut rng);
+
+        for game in positions.iter().step_by(9) {
+            for (i, (sym, symmetric)) in Symmetry::ALL.into_iter().zip(game.make_symmetric_positions()).enumerate() {
+                for mv in symmetric.move_indices() {
+                    let mut played_symmetric = symmetric.clone();
+                    played_symmetric.play_idx(mv).unwrap();
+
+                    let mut played_original = game.clone();
+                    played_original.apply_move_mirrored(mv, sym).unwrap();
+
+                    assert_eq!(played_original.make_symmetric_positions()[i], played_symmetric);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_explain_pass() {
+        assert_eq!(Game::new().explain_pass(), "Player X (Black) does not need to pass: 4 legal moves. Discs: X 2, O 2.");
+
+        let pass = Game::from_string("XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXO-\n\
+        XXXXXX--", Player::Two, false).unwrap();
+
+        assert_eq!(pass.explain_pass(), "Player O (White) must pass: no legal moves. Opponent has 3 legal moves. Discs: X 60, O 1.");
+
+        let mut over = pass.clone();
+        over.pass().unwrap();
+        over.play(7, 6).unwrap();
+
+        assert_eq!(over.explain_pass(), "Player O (White) must pass: no legal moves, and neither does the opponent, so the game is over. Discs: X 62, O 0.");
+    }
+
+    #[test]
+    fn test_move_bitmap() {
+        assert_eq!(Game::from_move_bitmap(0, Player::One).unwrap(), Game::new());
+
+        let mask = Game::new().legal_moves_mask();
+        assert_eq!(mask, 1 << 20 | 1 << 29 | 1 << 34 | 1 << 43);
+
+        for bit in 0..64 {
+            let game = Game::from_move_bitmap(1 << bit, Player::One);
+
+            if mask & 1 << bit != 0 {
+                let mut expected = Game::new();
+                expected.play_idx(bit).unwrap();
+
+                assert_eq!(game.unwrap(), expected);
+            } else {
+                assert_eq!(game.unwrap_err().to_string(), format!("Illegal move at bit {bit}"));
+            }
+        }
+
+        // f4 then f5, played in bit order
+        let mut expected = Game::new();
+        expected.play_idx(29).unwrap();
+        expected.play_idx(37).unwrap();
+
+        let game = Game::from_move_bitmap(1 << 29 | 1 << 37, Player::One).unwrap();
+        assert_eq!(game, expected);
+        assert_eq!(game.legal_moves_mask(), game.move_indices().iter().fold(0, |mask, idx| mask | 1 << idx));
+    }
+
+    #[test]
+    fn test_depth_first_search() {
+        let game = Game::new();
+
+        assert_eq!(game.perft(0), 1);
+        assert_eq!(game.perft(1), 4);
+        assert_eq!(game.perft(2), 12);
+        assert_eq!(game.perft(3), 56);
+        assert_eq!(game.perft(4), 244);
+
+        let mut visited = Vec::new();
+        game.depth_first_search(2, |game, depth| visited.push((game.clone(), depth)));
+
+        assert_eq!(visited.len(), 1 + 4 + 12);
+        assert_eq!(visited[0], (game.clone(), 0));
+        assert!(visited.iter().all(|(game, depth)| game.total_moves() == 4 + depth));
+    }
+
+    #[test]
+    fn test_random_position_at_move_n() {
+        let mut rng = fastrand::Rng::with_seed(37);
+
+        for n in [0, 1, 10, 30, 45] {
+            let game = Game::random_position_at_move_n(n, &mut rng).unwrap();
+            assert_eq!(game.total_moves(), 4 + n);
+        }
+
+        assert!(Game::random_position_at_move_n(61, &mut rng).is_err());
+
+        // random games rarely fill the whole board, but any that get there have 64 discs
+        for _ in 0..20 {
+            if let Ok(game) = Game::random_position_at_move_n(60, &mut rng) {
+                assert_eq!(game.total_moves(), 64);
+            }
+        }
+    }
+
+    #[test]
+    fn test_suggested_solver() {
+        let mut rng = fastrand::Rng::with_seed(41);
+
+        let opening = Game::new();
+        assert!(!opening.is_theoretically_solved());
+        assert_eq!(opening.suggested_solver(), SolverStrategy::RandomPlayout);
+
+        let early_midgame = Game::random_position_at_move_n(20, &mut rng).unwrap();
+        assert_eq!(early_midgame.suggested_solver(), SolverStrategy::HeuristicSearch(4));
+
+        let late_midgame = Game::random_position_at_move_n(40, &mut rng).unwrap();
+        assert_eq!(late_midgame.suggested_solver(), SolverStrategy::HeuristicSearch(6));
+
+        let endgame = Game::random_position_at_move_n(50, &mut rng).unwrap();
+        assert!(endgame.is_theoretically_solved());
+        assert_eq!(endgame.suggested_solver(), SolverStrategy::PerfectEndgame);
+    }
+
+    #[test]
+    fn test_normalized_eval() {
+        let mut rng = fastrand::Rng::with_seed(43);
+
+        for game in random_game(&mut rng) {
+            let normalized = game.normalized_eval();
+
+            assert!((-1.0..=1.0).contains(&normalized));
+            assert_eq!(normalized > 0.0, game.evaluate() > 0);
+        }
+
+        let winning = Game::from_string("XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX", Player::One, false).unwrap();
+
+        assert!(winning.normalized_eval() > 0.99);
+    }
+
+    #[test]
+    fn test_solve_with_book() {
+        let mut game = Game::new();
+        game.play_idx(29).unwrap();
+
+        let mut book = OpeningBook::new();
+
+        assert_eq!(game.solve_with_book(&book, 1), solve::solve_at_depth(&game, 1));
+
+        book.insert(game.clone(), vec![(2, 37)]);
+
+        assert_eq!(game.solve_with_book(&book, 1), vec![(BOOK_SCORE + 2, 37)]);
+
+        // symmetric positions are found too, with the move mapped onto their board
+        for (symmetry, symmetric) in Symmetry::ALL.into_iter().zip(game.make_symmetric_positions()) {
+            let moves = symmetric.solve_with_book(&book, 1);
+
+            assert_eq!(moves, vec![(BOOK_SCORE + 2, Game::map_move_to_symmetric(37, symmetry))]);
+            assert!(symmetric.move_indices().contains(&moves[0].1));
+        }
+
+        let mut off_book = game.clone();
+        off_book.play_idx(37).unwrap();
+        assert!(off_book.solve_with_book(&book, 1).iter().all(|(score, _)| *score < BOOK_SCORE));
+    }
+
+    #[test]
+    fn test_reachable_from_standard_start() {
+        let mut rng = fastrand::Rng::with_seed(53);
+
+        for game in random_game(&mut rng) {
+            assert!(game.is_reachable_from_standard_start());
+        }
+
+        // a player can drop to a single disc after the first move
+        let mut game = Game::new();
+        game.play_idx(29).unwrap();
+        assert!(game.is_reachable_from_standard_start());
+
+        let empty_centre = Game::from_string("--------\n\
+        --------\n\
+        --------\n\
+        ---X----\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        assert!(!empty_centre.is_reachable_from_standard_start());
+
+        let isolated = Game::from_string("-------O\n\
+        --------\n\
+        --------\n\
+        ---XO---\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        assert!(!isolated.is_reachable_from_standard_start());
+    }
+
+    #[test]
+    fn test_move_tree_string() {
+        let game = Game::new();
+
+        let tree = game.to_move_tree_string(2);
+        let lines = tree.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], format!("{} {}", game.to_string_compact(), game.evaluate()));
+        assert_eq!(lines.len(), 1 + 4 + 12);
+        assert!(lines[1].starts_with("|-- "));
+        assert!(lines[2].starts_with("    |-- "));
+
+        assert_eq!(game.to_move_tree_string(0).lines().count(), 1);
+        assert_eq!(Game::from_compressed_string(&game.to_string_compact()).unwrap(), game);
+    }
+
+    #[test]
+    fn test_best_continuation() {
+        let game = Game::new();
+        let continuation = game.get_best_continuation(2);
+
+        assert_eq!(continuation.len(), 2);
+        assert!(game.is_valid_sequence(&continuation.iter().map(|(mv, _)| *mv).collect::<Vec<_>>()));
+
+        // the first move is scored assuming the best reply that follows it
+        assert_eq!(continuation[0].1, -continuation[1].1);
+        assert_eq!(continuation[0].1, solve::solve_at_depth(&game, 1).iter().map(|(score, _)| *score).max().unwrap());
+
+        let mut rng = fastrand::Rng::with_seed(59);
+
+        for game in random_game(&mut rng).iter().step_by(5) {
+            let continuation = game.get_best_continuation(3);
+
+            assert!(continuation.len() <= 3);
+            assert!(game.is_valid_sequence(&continuation.iter().map(|(mv, _)| *mv).collect::<Vec<_>>()));
+        }
+
+        assert!(game.get_best_continuation(0).is_empty());
+    }
+
+    #[test]
+    fn test_best_response() {
+        let mut rng = fastrand::Rng::with_seed(271);
+
+        for start in random_game(&mut rng).iter().step_by(5).chain([&Game::new()]) {
+            let continuation = start.get_best_continuation(3);
+
+            if continuation.len() < 3 {
+                continue;
+            }
+
+            let line = continuation.iter().map(|(mv, _)| *mv).collect::<Vec<_>>();
+
+            // each player answers the other's move in turn, searching as deep as the rest of the line
+            let mut game = start.clone();
+            game.play_idx(line[0]).unwrap();
+
+            let reply = start.best_response(line[0], 1).unwrap().unwrap();
+            assert_eq!(reply, line[1]);
+
+            let answer = game.best_response(reply, 0).unwrap().unwrap();
+            assert_eq!(answer, line[2]);
+
+            game.play_idx(reply).unwrap();
+            game.play_idx(answer).unwrap();
+
+            let mut expected = start.clone();
+            for mv in line {
+                expected.play_idx(mv).unwrap();
+            }
+
+            assert_eq!(game, expected);
+        }
+
+        assert!(Game::new().best_response(0, 1).is_err());
+        assert!(Game::new().best_response(64, 1).is_err());
+    }
+
+    #[test]
+    fn test_score_display() {
+        let mut game = Game::new();
+
+        assert_eq!(game.move_number(), 0);
+        assert_eq!(game.score_display(), "X: 2 | O: 2");
+        assert_eq!(game.to_header_string(), "Move 0 | X: 2 | O: 2 | X to move");
+
+        game.play_idx(29).unwrap();
+
+        assert_eq!(game.move_number(), 1);
+        assert_eq!(game.score_display(), "X: 4 | O: 1");
+        assert_eq!(game.to_header_string(), "Move 1 | X: 4 | O: 1 | O to move");
+    }
+
+    #[test]
+    fn test_policy_vector() {
+        let mut rng = fastrand::Rng::with_seed(67);
+
+        for game in random_game(&mut rng).iter().step_by(3) {
+            let policy = game.policy_vector(1);
+            let moves = game.move_indices();
+
+            for (idx, probability) in policy.iter().enumerate() {
+                if moves.contains(&idx) {
+                    assert!(*probability > 0.0);
+                } else {
+                    assert_eq!(*probability, 0.0);
+                }
+            }
+
+            if moves.is_empty() {
+                assert_eq!(policy.iter().sum::<f32>(), 0.0);
+            } else {
+                assert!((policy.iter().sum::<f32>() - 1.0).abs() < 1e-5);
+            }
+
+            assert_eq!(policy, game.to_training_sample(1).policy);
+        }
+    }
+
+    #[test]
+    fn test_count_by_cell_type() {
+        let counts = Game::new().count_by_cell_type(Player::One);
+
+        assert_eq!(counts.len(), 5);
+        assert_eq!(counts[&CellType::Interior], 2);
+        assert_eq!(counts.values().sum::<usize>(), 2);
+
+        let corner = Game::from_string("XX------\n\
+        XX------\n\
+        --------\n\
+        ---XO---\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        --X-----", Player::One, false).unwrap();
+
+        let counts = corner.count_by_cell_type(Player::One);
+
+        assert_eq!(counts[&CellType::Corner], 1);
+        assert_eq!(counts[&CellType::CSquare], 2);
+        assert_eq!(counts[&CellType::XSquare], 1);
+        assert_eq!(counts[&CellType::Edge], 1);
+        assert_eq!(counts[&CellType::Interior], 2);
+        assert_eq!(corner.count_by_cell_type(Player::Two)[&CellType::Interior], 2);
+    }
+
+    #[test]
+    fn test_feature_tensors() {
+        let mut game = Game::new();
+        let tensor = game.to_feature_tensor();
+
+        assert_eq!(tensor[3][3], 1.0);
+        assert_eq!(tensor[4][4], 1.0);
+        assert_eq!(tensor[3][4], -1.0);
+        assert_eq!(tensor[4][3], -1.0);
+        assert_eq!(tensor.iter().flatten().filter(|value| **value == 0.0).count(), 60);
+
+        #[allow(deprecated)]
+        game.swap_players();
+        assert_eq!(game.to_feature_tensor()[3][3], -1.0);
+
+        let planes = game.to_multi_plane_features();
+
+        assert_eq!(planes[0][3][3], 1.0);
+        assert_eq!(planes[1][3][4], 1.0);
+        assert_eq!(planes[2][0][0], 1.0);
+        assert_eq!(planes.iter().map(|plane| plane.iter().flatten().sum::<f32>()).collect::<Vec<_>>(), vec![2.0, 2.0, 60.0]);
+    }
+
+    #[test]
+    fn test_bulk_solve() {
+        let mut rng = fastrand::Rng::with_seed(71);
+
+        let positions = (0..6).flat_map(|_| random_game(&mut rng).into_iter().filter(|game| 64 - game.total_moves() <= 4)).collect::<Vec<_>>();
+        assert!(!positions.is_empty());
+
+        // each move is scored from its player's side by a search of the position after it
+        let score_moves = |game: &Game, score: &dyn Fn(&Game) -> isize| game.moves().into_iter().map(|possible_move| {
+            let mut new_game = game.clone();
+            new_game.play_move(possible_move).unwrap();
+            (-score(&new_game), possible_move)
+        }).collect::<Vec<_>>();
+
+        let expected = positions.iter().map(|game| score_moves(game, &|new_game| new_game.endgame_search_with_empties(SIZE).unwrap())).collect::<Vec<_>>();
+        assert_eq!(Game::bulk_solve(&positions), expected);
+
+        let expected = positions.iter().map(|game| score_moves(game, &|new_game| solve::negamax_depth(new_game, 2, -(SIZE as isize), SIZE as isize, &solve::disc_difference).unwrap())).collect::<Vec<_>>();
+        assert_eq!(Game::bulk_solve_at_depth(&positions, 2), expected);
+
+        assert!(Game::bulk_solve(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_move_frequency() {
+        let games = [
+            GameRecord::new(vec![Some(29), Some(37)]),
+            GameRecord::new(vec![Some(29), Some(21)]),
+            GameRecord::new(vec![Some(20), None, Some(37)]),
+        ];
+
+        let table = Game::move_frequency_table(&games);
+
+        assert_eq!(table[29], 2);
+        assert_eq!(table[37], 2);
+        assert_eq!(table[21], 1);
+        assert_eq!(table[20], 1);
+        assert_eq!(table.iter().sum::<u32>(), 6);
+
+        let heatmap = Game::move_frequency_heatmap(&games);
+
+        assert_eq!(heatmap[29], 1.0);
+        assert_eq!(heatmap[21], 0.5);
+        assert_eq!(heatmap[0], 0.0);
+
+        assert_eq!(Game::move_frequency_heatmap(&[]), [0.0; 64]);
+    }
+
+    #[test]
+    fn test_random_move_weighted() {
+        let mut rng = fastrand::Rng::with_seed(73);
+
+        let mut weights = [0.0; 64];
+        weights[34] = 1.0;
+        weights[0] = 100.0;
+
+        for _ in 0..50 {
+            let mut game = Game::new();
+            assert_eq!(game.apply_random_move_weighted(&weights, &mut rng), Some(34));
+        }
+
+        let mut counts = [0; 64];
+        weights[20] = 3.0;
+
+        for _ in 0..2000 {
+            let mut game = Game::new();
+            counts[game.apply_random_move_weighted(&weights, &mut rng).unwrap()] += 1;
+        }
+
+        assert_eq!(counts[29] + counts[43], 0);
+        assert!((1300..1700).contains(&counts[20]), "{}", counts[20]);
+
+        // without any weight on a legal move, one is picked uniformly
+        let mut game = Game::new();
+        let chosen = game.apply_random_move_weighted(&[0.0; 64], &mut rng).unwrap();
+        assert!([20, 29, 34, 43].contains(&chosen));
+
+        let mut finished = random_game(&mut rng).pop().unwrap();
+        let before = finished.clone();
+        assert_eq!(finished.apply_random_move_weighted(&[1.0; 64], &mut rng), None);
+        assert_eq!(finished, before);
+    }
+
+    #[test]
+    fn test_endgame_search_with_empties() {
+        let mut rng = fastrand::Rng::with_seed(79);
+
+        for _ in 0..5 {
+            let positions = random_game(&mut rng);
+            let game = positions.iter().find(|game| game.num_empty_squares() == 6).unwrap();
+
+            assert_eq!(game.endgame_search_with_empties(6).unwrap(), solve::negamax_depth(game, 64, -(SIZE as isize), SIZE as isize, &solve::disc_difference).unwrap());
+
+            let err = game.endgame_search_with_empties(5).unwrap_err();
+            assert_eq!(err.downcast_ref::<ReversiError>(), Some(&ReversiError::TooManyEmpties { actual: 6, max: 5 }));
+        }
+
+        assert_eq!(Game::new().num_empty_squares(), 60);
+    }
+
+    #[test]
+    fn test_from_game_record_at_move() {
+        let mut rng = fastrand::Rng::with_seed(243);
+        let record = random_record(&mut rng);
+        let positions = record.replay().unwrap();
+
+        assert_eq!(Game::from_game_record_at_move(&record, 0).unwrap(), Game::new());
+        assert_eq!(Game::from_game_record_at_move(&record, 5).unwrap(), positions[5]);
+        assert_eq!(Game::from_game_record_at_move(&record, record.moves.len()).unwrap(), *positions.last().unwrap());
+
+        assert!(Game::from_game_record_at_move(&record, record.moves.len() + 1).is_err());
+
+        let illegal = GameRecord::new(vec![Some(29), Some(0)]);
+        assert!(Game::from_game_record_at_move(&illegal, 1).is_ok());
+        assert!(Game::from_game_record_at_move(&illegal, 2).is_err());
+    }
+
+    #[test]
+    fn test_tactical_score() {
+        assert_eq!(Game::new().tactical_score(), 0);
+
+        // X wins immediately by taking a1, and O (with no moves of its own) has to deal with it
+        let board = "-OXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX";
+
+        let winning = Game::from_string(board, Player::One, false).unwrap();
+        let blocking = Game::from_string(board, Player::Two, false).unwrap();
+
+        assert_eq!(winning.tactical_score(), 100 - 10);
+        assert_eq!(blocking.tactical_score(), -50 + 10);
+
+        let exposed = Game::from_string("--------\n\
+        -X------\n\
+        --XO----\n\
+        ---XO---\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        assert_eq!(exposed.tactical_score(), -10);
+    }
+
+    #[test]
+    fn test_explain_move() {
+        let game = Game::new();
+
+        assert_eq!(game.count_flips(43), 1);
+        assert_eq!(game.count_flips(0), 0);
+        assert_eq!(game.count_flips(64), 0);
+        assert_eq!(game.explain_move(43).unwrap(), "flips 1 disc");
+        assert!(game.explain_move(0).is_err());
+
+        assert!(Game::is_corner_move(0));
+        assert!(Game::is_corner_move(63));
+        assert!(!Game::is_corner_move(9));
+
+        // X taking c3 flips c4, and lines up a1 behind O's b2
+        let threat = Game::from_string("--------\n\
+        -O------\n\
+        --------\n\
+        --O-----\n\
+        --X-----\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        assert_eq!(threat.explain_move(Game::san_to_move("c3").unwrap()).unwrap(), "flips 1 disc, threatens corner at a1");
+
+        // O taking b2 flips c3, which X can then outflank from a1
+        let mut given = Game::from_string("--------\n\
+        --------\n\
+        --X-----\n\
+        ---O----\n\
+        ----X---\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        assert_eq!(given.explain_move(9).unwrap(), "flips 1 disc, gives up corner at a1");
+
+        given.play_idx(9).unwrap();
+
+        assert_eq!(given.explain_move(0).unwrap(), "flips 3 discs, takes corner a1");
+    }
+
+    #[test]
+    fn test_bitstring() {
+        let encoded = Game::new().encode_as_bitstring();
+
+        assert_eq!(encoded.len(), 129);
+        assert_eq!(&encoded[27..29], "10");
+        assert_eq!(&encoded[64 + 27..64 + 29], "01");
+        assert!(encoded.ends_with('X'));
+
+        let mut rng = fastrand::Rng::with_seed(248);
+
+        for game in random_game(&mut rng) {
+            assert_eq!(Game::decode_from_bitstring(&game.encode_as_bitstring()).unwrap(), game);
+        }
+
+        let overlapping = format!("{}{}X", "1".repeat(64), "1".repeat(64));
+        assert!(Game::decode_from_bitstring(&overlapping).is_err());
+        assert!(Game::decode_from_bitstring(&encoded[..128]).is_err());
+        assert!(Game::decode_from_bitstring(&encoded.replace('X', "-")).is_err());
+        assert!(Game::decode_from_bitstring(&encoded.replacen('0', "2", 1)).is_err());
+    }
+
+    #[test]
+    fn test_count_captures_for_all_moves() {
+        assert_eq!(Game::new().count_captures_for_all_moves(), vec![(1, 20), (1, 29), (1, 34), (1, 43)]);
+
+        let game = Game::from_string("--------\n\
+        --------\n\
+        --------\n\
+        -XOOO---\n\
+        ---OX---\n\
+        ---O----\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        let captures = game.count_captures_for_all_moves();
+
+        assert_eq!(captures[0], (3, 29));
+        assert!(captures.windows(2).all(|pair| pair[0].0 >= pair[1].0));
+        assert_eq!(captures.len(), game.moves().len());
+
+        let mut rng = fastrand::Rng::with_seed(249);
+
+        for game in random_game(&mut rng) {
+            for (flips, idx) in game.count_captures_for_all_moves() {
+                assert_eq!(flips, game.count_flips(idx));
+            }
+        }
+    }
+
+    #[test]
+    fn test_is_ladder() {
+        assert!(!Game::new().is_ladder());
+
+        let ladder = Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        OX------", Player::One, false).unwrap();
+
+        assert!(ladder.is_ladder());
+
+        let mut after = ladder.clone();
+        after.play_idx(2).unwrap();
+
+        // O still has its one move, but X is left with none
+        assert!(!after.is_ladder());
+    }
+
+    #[test]
+    fn test_score_bounds() {
+        assert_eq!(Game::new().score_bounds(), (-64, 64));
+
+        let mut rng = fastrand::Rng::with_seed(251);
+        let mut tightened = false;
+
+        for _ in 0..10 {
+            for game in random_game(&mut rng) {
+                let (lower, upper) = game.score_bounds();
+
+                let score = if game.num_empty_squares() <= 8 {
+                    game.endgame_search_with_empties(8).unwrap()
+                } else {
+                    solve::negamax_depth(&game, 1, -(SIZE as isize), SIZE as isize, &solve::disc_difference).unwrap()
+                };
+
+                assert!(lower <= score && score <= upper, "{score} outside ({lower}, {upper})");
+
+                tightened |= lower > -64 || upper < 64;
+            }
+        }
+
+        assert!(tightened);
+    }
+
+    #[test]
+    fn test_position_code() {
+        let start = Game::new();
+        assert_eq!(start.serialize_as_position_code(), (1 << 27 | 1 << 36) | (1 << 28 | 1 << 35) << 64);
+
+        let mut rng = fastrand::Rng::with_seed(252);
+        let mut codes = HashMap::new();
+
+        for _ in 0..10 {
+            for game in random_game(&mut rng) {
+                for player in [Player::One, Player::Two] {
+                    let game = Game::from_compressed_string(&format!("{}:{}", if player == Player::One { 'X' } else { 'O' }, &game.to_string_compact()[2..])).unwrap();
+                    let code = game.serialize_as_position_code();
+
+                    assert_eq!(Game::from_position_code(code).unwrap(), game);
+                    assert_eq!(*codes.entry(code).or_insert_with(|| game.clone()), game);
+                }
+            }
+        }
+
+        let full = Game::from_compressed_string(&format!("O:{}{}", "X".repeat(63), "O")).unwrap();
+        assert_eq!(Game::from_position_code(full.serialize_as_position_code()).unwrap(), full);
+
+        assert!(Game::from_position_code(0b11 | 0b1 << 64).is_err());
+    }
+
+    #[test]
+    fn test_auto_play_game() {
+        let rng = std::cell::RefCell::new(fastrand::Rng::with_seed(253));
+        let random_player = |game: &Game| {
+            let moves = game.move_indices();
+            Some(moves[rng.borrow_mut().usize(..moves.len())])
+        };
+
+        for _ in 0..10 {
+            let (game, history) = Game::auto_play_game(random_player, random_player);
+
+            assert!(game.terminal_score().is_some());
+            assert_eq!(GameRecord::new(history.clone()).replay().unwrap().last(), Some(&game));
+            assert_eq!(history.iter().flatten().count(), game.move_number());
+            assert_eq!(game.move_history().iter().map(|entry| entry.move_idx).collect::<Vec<_>>(), history);
+        }
+
+        let first_move = |game: &Game| game.move_indices().first().copied();
+        let (game, history) = Game::auto_play_game(first_move, |_| None);
+
+        assert_eq!(history, vec![Some(34)]);
+        assert_eq!(game.move_number(), 1);
+    }
+
+    #[test]
+    fn test_eval_edge_stability() {
+        assert_eq!(Game::new().eval_edge_stability(), 0);
+
+        let top_row = "XXXXXXXX\n\
+        --------\n\
+        --------\n\
+        ---XO---\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        --------";
+
+        // the corners also start runs down the left and right columns, one disc each
+        assert_eq!(Game::from_string(top_row, Player::One, false).unwrap().eval_edge_stability(), 4);
+        assert_eq!(Game::from_string(top_row, Player::Two, false).unwrap().eval_edge_stability(), -4);
+
+        // a full edge counts every disc, a partial one only the runs from its corners: the top
+        // edge scores (3 - 5) / 2, the bottom 4 / 2, and the side columns a disc at each end
+        let mixed = Game::from_string("XXOOOOOX\n\
+        --------\n\
+        --------\n\
+        ---XO---\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        XXXO-OOX", Player::One, false).unwrap();
+
+        assert_eq!(mixed.eval_edge_stability(), -1 + 2 + 1 + 1);
+    }
+
+    #[test]
+    fn test_play_all_legal_moves() {
+        let game = Game::new();
+        let children = game.play_all_legal_moves();
+
+        assert_eq!(children.len(), game.moves().len());
+        assert!(children.iter().all(|child| child.total_moves() == game.total_moves() + 1));
+
+        // O has nothing to flip, but X can take b1
+        let pass = Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        let children = pass.play_all_legal_moves();
+
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0], Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap());
+
+        let over = Game::from_string("XX------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        assert!(over.play_all_legal_moves().is_empty());
+
+        assert_eq!(game.legal_turns(), game.move_indices().into_iter().map(Some).collect::<Vec<_>>());
+        assert_eq!(pass.legal_turns(), vec![None]);
+        assert!(over.legal_turns().is_empty());
+
+        // taking each turn in place and undoing it gives the same children
+        for position in [game, pass] {
+            let mut played = position.clone();
+
+            for (turn, child) in position.legal_turns().into_iter().zip(position.play_all_legal_moves()) {
+                played.play_turn(turn).unwrap();
+                assert_eq!(played, child);
+                assert_eq!(played.move_history().last().unwrap().move_idx, turn);
+
+                played.undo().unwrap();
+                assert_eq!(played, position);
+            }
+        }
+
+        assert!(Game::new().play_turn(None).is_err());
+    }
+
+    #[test]
+    fn test_to_pv_board_string() {
+        let game = Game::new();
+
+        // e3, d3, c3
+        let pv = [20, 19, 18];
+        assert!(game.is_valid_sequence(&pv));
+
+        assert_eq!(game.to_pv_board_string(&pv), "--------\n\
+        --------\n\
+        --321---\n\
+        ---XO---\n\
+        ---OX---\n\
+        --------\n\
+        --------\n\
+        --------\n");
+
+
+        // numbers widen to fit the longest one
+        let first_move = |game: &Game| game.move_indices().first().copied();
+        let pv = Game::auto_play_game(first_move, first_move).1
+            .into_iter().flatten().take(10).collect::<Vec<_>>();
+
+        let board = game.to_pv_board_string(&pv);
+        assert!(board.lines().all(|line| line.len() == 16));
+        assert!(board.contains(&format!("{:>2}", 10)) && board.contains(&format!("{:>2}", 1)));
+    }
+
+    #[test]
+    fn test_game_result() {
+        assert!(!Game::new().is_game_over());
+        assert_eq!(Game::new().result(), None);
+
+        // O has to pass, but X can still move
+        let pass = Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        assert!(!pass.is_game_over());
+        assert_eq!(pass.result(), None);
+
+        // neither player can move once O is wiped out
+        let wiped_out = Game::from_string("XX------\n\
+        --------\n\
+        --------\n\
+        ---X----\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        assert!(wiped_out.is_game_over());
+        assert_eq!(wiped_out.result(), Some(GameResult::Win(Player::One)));
+
+        let draw = Game::from_string("XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        XXXXXXXX\n\
+        OOOOOOOO\n\
+        OOOOOOOO\n\
+        OOOOOOOO\n\
+        OOOOOOOO", Player::One, false).unwrap();
+
+        assert!(draw.is_game_over());
+        assert_eq!(draw.result(), Some(GameResult::Draw));
+    }
+
+    #[test]
+    fn test_count_legal_moves_naive() {
+        assert_eq!(Game::new().count_legal_moves_naive(), 4);
+
+        let mut rng = fastrand::Rng::with_seed(71);
+        let mut checked = 0;
+
+        while checked < 100 {
+            for game in random_game(&mut rng).into_iter().step_by(4) {
+                assert_eq!(game.count_legal_moves_naive(), game.moves().len());
+                checked += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_pass() {
+        let mut game = Game::new();
+
+        assert!(game.pass().is_err());
+        assert_eq!(game, Game::new());
+
+        let mut pass = Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        pass.pass().unwrap();
+
+        assert_eq!(pass.current_player(), Player::One);
+        assert!(pass.pass().is_err());
+    }
+
+    #[test]
+    fn test_pgn() {
+        // the Tiger opening (f5 d6 c3 d3 c4), mirrored left to right as this board starts with
+        // the discs the other way around
+        let tiger = "[Event \"Casual game\"]\n\
+        [Date \"2023.07.01\"]\n\
+        [Black \"Alice\"]\n\
+        [White \"Bob\"]\n\
+        [Result \"*\"]\n\
+        \n\
+        1. c5 e6 2. f3 e3 3. f4 *\n";
+
+        let (game, moves, metadata) = Game::from_pgn_string(tiger).unwrap();
+
+        let expected = ["c5", "e6", "f3", "e3", "f4"].map(|san| Some(Game::san_to_move(san).unwrap()));
+        assert_eq!(moves, expected);
+        assert_eq!(metadata, GameMetadata {
+            black_player: "Alice".to_string(),
+            white_player: "Bob".to_string(),
+            date: Some("2023.07.01".to_string()),
+            event: Some("Casual game".to_string()),
+        });
+
+        assert_eq!(game, GameRecord::new(moves.clone()).replay().unwrap().pop().unwrap());
+        assert_eq!(Game::new().to_pgn_string(&moves, &metadata), tiger);
+
+        // numbers attached to moves, unknown tags and comments
+        let (_, compact_moves, compact_metadata) = Game::from_pgn_string("[Black \"Alice\"]\n[Date \"????.??.??\"]\n1.c5 {the usual} e6 2.f3 e3 3.f4").unwrap();
+        assert_eq!(compact_moves, expected);
+        assert_eq!(compact_metadata.date, None);
+
+        assert!(Game::from_pgn_string("1. c5 c5").is_err());
+        assert!(Game::from_pgn_string("1. -- c5").is_err());
+
+        let mut rng = fastrand::Rng::with_seed(73);
+
+        for _ in 0..10 {
+            let record = random_record(&mut rng);
+            let final_position = record.replay().unwrap().pop().unwrap();
+
+            let pgn = Game::new().to_pgn_string(&record.moves, &GameMetadata::default());
+            assert!(!pgn.contains("[Result \"*\"]"));
+
+            let (game, moves, _) = Game::from_pgn_string(&pgn).unwrap();
+            assert_eq!(moves, record.moves);
+            assert_eq!(game, final_position);
+        }
+    }
+
+    #[test]
+    fn test_score() {
+        assert_eq!(Game::new().score(), (2, 2));
+
+        let mut game = Game::new();
+        game.play_move(game.moves()[0]).unwrap();
+
+        assert_eq!(game.score(), (4, 1));
+        assert_eq!(game.count_discs_for(Player::One), 4);
+        assert_eq!(game.count_discs_for(Player::Two), 1);
+
+        let mut rng = fastrand::Rng::with_seed(79);
+
+        for game in random_game(&mut rng) {
+            let (player_one, player_two) = game.score();
+            assert_eq!(player_one + player_two, game.total_moves());
+        }
+    }
+
+    #[test]
+    fn test_solve_first_move() {
+        let mut rng = fastrand::Rng::with_seed(83);
+        let mut checked = 0;
+
+        while checked < 20 {
+            for game in random_game(&mut rng).into_iter().filter(|game| game.total_moves() >= SIZE - 8) {
+                match game.solve_first_move() {
+                    // the move keeps the position's exact score for the player making it
+                    Some(best_move) => {
+                        let mut after = game.clone();
+                        after.play_idx(best_move).unwrap();
+
+                        assert_eq!(-after.endgame_search_with_empties(SIZE).unwrap(), game.endgame_search_with_empties(SIZE).unwrap());
+                    }
+                    None => assert!(game.move_indices().is_empty()),
+                }
+
+                checked += 1;
+            }
+        }
+    }
+
+    #[test]
+    fn test_accessors() {
+        let mut game = Game::new();
+
+        assert_eq!(game.current_player(), Player::One);
+        assert_eq!(game.board().population_count(), (2, 2));
+        assert_eq!(game.board().get_cell(3, 3), Cell::Player(Player::One));
+        assert_eq!(game.board().get_cell(4, 4), Cell::Player(Player::One));
+        assert_eq!(game.board().get_cell(3, 4), Cell::Player(Player::Two));
+        assert_eq!(game.board().get_cell(4, 3), Cell::Player(Player::Two));
+
+        game.play_move(game.moves()[0]).unwrap();
+        assert_eq!(game.current_player(), Player::Two);
+
+        // editing the board keeps the hash in step with it
+        let mut edited = Game::new();
+        edited.board_mut().set_cell(0, 0, Cell::Player(Player::Two));
+        edited.board_mut().set_cell(0, 0, Cell::Empty);
+
+        assert_eq!(edited, Game::new());
+        assert_eq!(edited.hash(), edited.board().compute_hash());
+
+        *edited.board_mut() = Board::new();
+        assert_eq!(edited.hash(), Board::new().compute_hash());
+        assert!(edited.moves().is_empty());
+    }
+
+    #[test]
+    fn test_is_zugzwang() {
+        assert!(!Game::new().is_zugzwang());
+
+        // X is ahead, but can only play a3 or a4, and either one opens up the left column
+        let zugzwang = Game::from_string("OXXXXXXX\n\
+        OOXOOXXX\n\
+        -OXXOXXX\n\
+        -XXXXOXX\n\
+        OXXXOXXX\n\
+        OXXXXOXX\n\
+        OX-XXXOX\n\
+        O-XXXXOO", Player::One, false).unwrap();
+
+        assert_eq!(zugzwang.moves(), vec![Move::new(0, 2), Move::new(0, 3)]);
+        assert!(zugzwang.is_zugzwang());
+
+        // a forced pass is not zugzwang
+        let pass = Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::Two, false).unwrap();
+
+        assert!(!pass.is_zugzwang());
+    }
+
+    #[test]
+    fn test_null_move() {
+        let mut game = Game::new();
+        game.apply_null_move();
+
+        assert_eq!(game.current_player(), Player::Two);
+        assert_eq!(game.board(), Game::new().board());
+
+        game.apply_null_move();
+        assert_eq!(game, Game::new());
+
+        assert!(Game::new().is_null_move_safe());
+
+        // X can end the game at once by taking b1
+        let winning = Game::from_string("XO------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+
+        assert!(!winning.is_null_move_safe());
+
+        let mut rng = fastrand::Rng::with_seed(89);
+
+        for game in random_game(&mut rng) {
+            if game.is_theoretically_solved() || game.moves().is_empty() {
+                assert!(!game.is_null_move_safe());
+            }
+        }
+    }
+
+    #[test]
+    fn test_from_moves() {
+        assert_eq!(Game::from_moves(&[]).unwrap(), Game::new());
+
+        // e3, d3, c3
+        let mut expected = Game::new();
+        for idx in [20, 19, 18] {
+            expected.play_idx(idx).unwrap();
+        }
+
+        assert_eq!(Game::from_moves(&[Some(20), Some(19), Some(18)]).unwrap(), expected);
+
+        // the random games of `reversi-solver random`, cut short like `--backtrack` does
+        let mut rng = fastrand::Rng::with_seed(97);
+
+        for _ in 0..10 {
+            let record = random_record(&mut rng);
+            let positions = record.replay().unwrap();
+
+            for backtrack in [0, 1, 5] {
+                let kept = record.moves.len() - backtrack;
+                assert_eq!(Game::from_moves(&record.moves[..kept]).unwrap(), positions[kept]);
+            }
+        }
+
+        let illegal = Game::from_moves(&[Some(20), Some(0)]).unwrap_err().to_string();
+        assert!(illegal.starts_with("Move 2 (a1): Invalid move, in position O:"), "{illegal}");
+
+        let early_pass = Game::from_moves(&[None]).unwrap_err().to_string();
+        assert!(early_pass.starts_with("Move 1 (pass): "), "{early_pass}");
+        assert!(early_pass.contains(&Game::new().to_string_compact()), "{early_pass}");
+
+        assert!(Game::from_moves(&[Some(64)]).unwrap_err().to_string().starts_with("Move 1 (64): "));
+    }
+
+    #[test]
+    fn test_move_history() {
+        let mut game = Game::new();
+        assert!(game.move_history().is_empty());
+
+        // e3, d3, c3, f3
+        for idx in [20, 19, 18, 21] {
+            game.play_idx(idx).unwrap();
+        }
+
+        assert_eq!(game.move_count(), 4);
+        assert_eq!(game.move_history().iter().map(|entry| (entry.move_idx, entry.player)).collect::<Vec<_>>(), vec![
+            (Some(20), Player::One),
+            (Some(19), Player::Two),
+            (Some(18), Player::One),
+            (Some(21), Player::Two),
+        ]);
+
+        assert!(game.play_idx(0).is_err());
+        assert_eq!(game.move_count(), 4);
+
+        let mut rng = fastrand::Rng::with_seed(98);
+
+        for _ in 0..5 {
+            let record = random_record(&mut rng);
+            let game = Game::from_moves(&record.moves).unwrap();
+
+            assert_eq!(game.move_history().iter().map(|entry| entry.move_idx).collect::<Vec<_>>(), record.moves);
+            assert!(game.move_history().windows(2).all(|pair| pair[0].player != pair[1].player));
+        }
+    }
+
+    #[test]
+    fn test_undo() {
+        let mut rng = fastrand::Rng::with_seed(99);
+
+        for _ in 0..5 {
+            let record = random_record(&mut rng);
+            let mut game = Game::from_moves(&record.moves).unwrap();
+            let mut positions = record.replay().unwrap();
+            positions.pop();
+
+            // every legal move (or pass) taken back leaves exactly the position before it
+            for before in &positions {
+                let history = before.move_history().to_vec();
+
+                for mut after in before.play_all_legal_moves() {
+                    let mut child = before.clone();
+                    match after.move_history().last().unwrap().move_idx {
+                        Some(idx) => child.play_idx(idx).unwrap(),
+                        None => child.pass().unwrap(),
+                    }
+
+                    after.undo().unwrap();
+                    child.undo().unwrap();
+
+                    for undone in [after, child] {
+                        assert_eq!(undone, *before);
+                        assert_eq!(undone.hash(), before.hash());
+                        assert_eq!(undone.move_history(), history);
+                        assert_eq!(undone.to_string_compact(), before.to_string_compact());
+                    }
+                }
+            }
+
+            // undoing a whole game walks back through every position
+            while let Some(before) = positions.pop() {
+                game.undo().unwrap();
+                assert_eq!(game, before);
+                assert_eq!(game.hash(), before.hash());
+                assert_eq!(game.move_history(), before.move_history());
+            }
+
+            assert!(game.undo().is_err());
+            assert_eq!(game, Game::new());
+        }
+    }
+
+    #[test]
+    fn test_apply_move_for_player() {
+        // d6 is one of X's starting moves, not O's
+        let mut game = Game::new();
+        game.apply_move_for_player(43, Player::Two).unwrap_err();
+        assert_eq!(game, Game::new());
+
+        let mut expected = Game::new();
+        expected.play_idx(43).unwrap();
+
+        let mut out_of_turn = Game::new();
+        out_of_turn.apply_move_for_player(43, Player::One).unwrap();
+        assert_eq!(out_of_turn, expected);
+
+        // O's first moves are the mirror of X's, playable from the start when out of turn
+        let mut game = Game::new();
+        let o_moves = {
+            let mut opponent = Game::new();
+            #[allow(deprecated)]
+            opponent.swap_players();
+            opponent.move_indices()
+        };
+
+        game.apply_move_for_player(o_moves[0], Player::Two).unwrap();
+        assert_eq!(game.to_string_compact().chars().next(), Some('X'));
+        assert_eq!(game.move_history().last().unwrap().player, Player::Two);
+        assert_eq!(game.count_discs_for(Player::Two), 4);
+
+        // the same player can move twice in a row
+        let moves = {
+            let mut again = game.clone();
+            #[allow(deprecated)]
+            again.swap_players();
+            again.move_indices()
+        };
+        game.apply_move_for_player(moves[0], Player::Two).unwrap();
+        assert_eq!(game.to_string_compact().chars().next(), Some('X'));
+
+        let before = game.clone();
+        assert!(game.apply_move_for_player(0, Player::One).is_err());
+        assert!(game.apply_move_for_player(64, Player::Two).is_err());
+        assert_eq!(game.to_string_compact(), before.to_string_compact());
+    }
+
+    #[test]
+    fn test_latex_board() {
+        let latex = Game::new().to_latex_board();
+
+        assert!(latex.starts_with("% Requires \\usepackage{tikz}\n\\begin{tikzpicture}"), "{latex}");
+        assert!(latex.ends_with("\\end{tikzpicture}\n"), "{latex}");
+        assert!(latex.contains("\\draw (0,0) grid (8,8);"), "{latex}");
+        assert!(latex.contains("\\node at (0.5,8.5) {a};") && latex.contains("\\node at (-0.5,0.5) {8};"), "{latex}");
+
+        // two discs each, and a cross for each of X's four moves
+        assert_eq!(latex.matches("\\fill").count(), 2);
+        assert_eq!(latex.matches("\\draw (").count() - 1, 2);
+        assert_eq!(latex.matches("$\\times$").count(), 4);
+
+        // e3, the first of X's moves in index order, is column 4 and row 3 counted from the top
+        assert!(latex.contains("\\node at (4.5,5.5) {$\\times$};"), "{latex}");
+
+        let mut game = Game::new();
+        game.play_idx(20).unwrap();
+        assert!(game.to_latex_board().contains("\\fill (4.5,5.5) circle (0.4);"));
+        assert!(!game.to_latex_board().contains("\\node at (4.5,5.5)"));
+    }
+
+    #[test]
+    fn test_json() {
+        let json = Game::new().to_json();
+        assert_eq!(json, format!("{{\"board\":\"{}\",\"current_player\":\"X\",\"start\":\"{}\",\"history\":[]}}", &Game::new().to_string_compact()[2..], Game::new().to_string_compact()));
+        assert_eq!(Game::from_json(&json).unwrap(), Game::new());
+
+        let mut rng = fastrand::Rng::with_seed(272);
+
+        for _ in 0..5 {
+            let record = random_record(&mut rng);
+
+            for kept in [0, 1, 10, record.moves.len()] {
+                let game = Game::from_moves(&record.moves[..kept]).unwrap();
+                let loaded = Game::from_json(&game.to_json()).unwrap();
+
+                assert_eq!(loaded, game);
+                assert_eq!(loaded.hash(), game.hash());
+                assert_eq!(loaded.move_history(), game.move_history());
+                assert_eq!(loaded.to_json(), game.to_json());
+            }
+
+            // every truncated save is rejected
+            let json = Game::from_moves(&record.moves).unwrap().to_json();
+            for end in 0..json.len() {
+                assert!(Game::from_json(&json[..end]).is_err(), "{}", &json[..end]);
+            }
+        }
+
+        // auto-played games, including ones with forced passes
+        let rng = std::cell::RefCell::new(fastrand::Rng::with_seed(10));
+        let random_player = |game: &Game| {
+            let moves = game.move_indices();
+            Some(moves[rng.borrow_mut().usize(..moves.len())])
+        };
+
+        let mut passed = 0;
+
+        for _ in 0..30 {
+            let (game, history) = Game::auto_play_game(random_player, random_player);
+            let loaded = Game::from_json(&game.to_json()).unwrap();
+
+            assert_eq!(loaded, game);
+            assert_eq!(loaded.move_history().iter().map(|entry| entry.move_idx).collect::<Vec<_>>(), history);
+
+            if history.contains(&None) {
+                passed += 1;
+            }
+        }
+
+        assert!(passed > 0);
+
+        // a history played from a position other than the start
+        let mut game = Game::from_string("--------\n\
+        --------\n\
+        --------\n\
+        ---OXO--\n\
+        ---XO---\n\
+        --------\n\
+        --------\n\
+        --------", Player::One, false).unwrap();
+        game.play_idx(game.move_indices()[0]).unwrap();
+
+        let json = game.to_json();
+        assert!(json.contains(&format!("\"start\":\"X:{}", "-".repeat(27))), "{json}");
+        assert_eq!(Game::from_json(&json).unwrap().move_history(), game.move_history());
+
+        // whitespace and unknown fields are fine, and the start defaults to the standard one
+        let mut started = Game::new();
+        started.play_idx(20).unwrap();
+        let spaced = format!(" {{ \"history\" : [ \"e3\" ] ,\n \"extra\": [1.5, true, {{}}],\n \"board\": \"{}\", \"current_player\": \"O\" }}\n", &started.to_string_compact()[2..]);
+        assert_eq!(Game::from_json(&spaced).unwrap(), started);
+
+        let valid = started.to_json();
+        for corrupted in [
+            String::new(),
+            "[]".to_string(),
+            "{}".to_string(),
+            valid.replace("\"e3\"", "\"z9\""),
+            valid.replace("\"e3\"", "\"d3\""),
+            valid.replace("\"e3\"", "3"),
+            valid.replace("\"current_player\":\"O\"", "\"current_player\":\"X\""),
+            valid.replace("\"current_player\":\"O\"", "\"current_player\":\"Q\""),
+            valid.replace("\"history\":[\"e3\"]", "\"history\":[]"),
+            valid.replace("\"history\":[\"e3\"]", "\"history\":\"e3\""),
+            valid.replace("\"board\":\"", "\"board\":\"-"),
+            format!("{valid}}}"),
+            valid.replace(',', ""),
+        ] {
+            assert!(Game::from_json(&corrupted).is_err(), "{corrupted}");
+        }
+    }
+
+    #[test]
+    fn test_game_bytes() {
+        let mut rng = fastrand::Rng::with_seed(274);
+
+        for game in random_game(&mut rng).into_iter().chain(random_game(&mut rng)) {
+            let bytes = game.to_bytes();
+
+            assert_eq!(bytes[..16], game.board().to_bytes());
+            assert_eq!(bytes[16], if game.current_player() == Player::One { 0 } else { 1 });
+
+            let decoded = Game::from_bytes(&bytes).unwrap();
+            assert_eq!(decoded, game);
+            assert_eq!(decoded.hash(), game.hash());
+        }
+
+        let mut bytes = Game::new().to_bytes();
+        bytes[16] = 2;
+        assert!(Game::from_bytes(&bytes).is_err());
+
+        let mut overlapping = [0xff; 17];
+        overlapping[16] = 0;
+        assert!(Game::from_bytes(&overlapping).is_err());
+    }
+
+    #[test]
+    fn test_forced_passes_in_history() {
+        let undo_all = |mut game: Game| {
+            while game.undo().is_ok() {}
+            game
+        };
+
+        let mut rng = fastrand::Rng::with_seed(267);
+        let mut passes = 0;
+
+        for _ in 0..20 {
+            let record = random_record(&mut rng);
+            passes += record.moves.iter().filter(|recorded_move| recorded_move.is_none()).count();
+
+            // SGF with the passes left out, and with them written as `PA`
+            let mut player = Player::One;
+            let (mut inferred, mut explicit) = (String::from("(;GM[2]"), String::from("(;GM[2]"));
+
+            for recorded_move in &record.moves {
+                let color = if player == Player::One { 'B' } else { 'W' };
+                let value = recorded_move.map_or("PA".to_string(), Game::move_to_san);
+
+                if recorded_move.is_some() {
+                    inferred.push_str(&format!(";{color}[{value}]"));
+                }
+                explicit.push_str(&format!(";{color}[{value}]"));
+
+                player = player.opponent();
+            }
+
+            for sgf in [inferred + ")", explicit + ")"] {
+                let game = Game::from_sgf(&sgf).unwrap();
+                let history = game.move_history().iter().map(|entry| entry.move_idx).collect::<Vec<_>>();
+
+                // a pass at the very end is never inferred
+                assert_eq!(history, record.moves[..history.len()]);
+                assert!(record.moves[history.len()..].iter().all(Option::is_none));
+                assert_eq!(undo_all(game), Game::new());
+            }
+
+            let played = record.moves.iter().flatten().count();
+            let game = Game::random_position_at_move_n(played, &mut rng).unwrap();
+            assert_eq!(game.move_history().iter().filter(|entry| entry.move_idx.is_some()).count(), played);
+            assert_eq!(undo_all(game), Game::new());
+        }
+
+        assert!(passes > 0);
+    }
+}
@@ -0,0 +1,192 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use reversi_solver::{Game, board::{Move, Player, SIZE}, solve::{self, disc_difference, iterative_deepening_solve, solve_with_timeout, negamax, negamax_depth, negamax_with_table, order_moves, negamax_mtdf, TranspositionTable}};
+
+    use crate::common::random_game;
+
+    #[test]
+    fn test_mtdf() {
+        let mut rng = fastrand::Rng::with_seed(19);
+
+        for game in random_game(&mut rng).iter().step_by(4) {
+            for depth in 0..=3 {
+                let expected = negamax_depth(game, depth, -(SIZE as isize), SIZE as isize, &disc_difference).unwrap();
+
+                for first_guess in [0, expected, -20, 20] {
+                    let mut tt = TranspositionTable::new();
+                    assert_eq!(negamax_mtdf(game, first_guess, depth, &mut tt).unwrap(), expected);
+                }
+            }
+        }
+
+        let mut tt = TranspositionTable::new();
+        assert_eq!(negamax_mtdf(&Game::new(), 0, 3, &mut tt).unwrap(), negamax_depth(&Game::new(), 3, -(SIZE as isize), SIZE as isize, &disc_difference).unwrap());
+        assert!(!tt.is_empty());
+    }
+
+    /// [`negamax`] without any pruning.
+    fn full_negamax(game: &Game) -> isize {
+        let moves = game.move_indices();
+
+        if moves.is_empty() {
+            return 0;
+        }
+
+        if moves.iter().any(|possible_move| game.is_winning_move_idx(*possible_move, game.current_player()).unwrap()) {
+            return (SIZE as isize + 1 - game.total_moves() as isize) / 2;
+        }
+
+        moves.into_iter().map(|possible_move| {
+            let mut new_game = game.clone();
+            new_game.play_idx(possible_move).unwrap();
+            -full_negamax(&new_game)
+        }).max().unwrap()
+    }
+
+    #[test]
+    fn test_alpha_beta_negamax() {
+        let mut rng = fastrand::Rng::with_seed(251);
+
+        for _ in 0..5 {
+            for game in random_game(&mut rng).into_iter().filter(|game| SIZE - game.total_moves() <= 7) {
+                let expected = full_negamax(&game);
+
+                assert_eq!(negamax(&game, -(SIZE as isize), SIZE as isize).unwrap(), expected);
+
+                // solve scores each move by the exact disc difference for the player making it
+                let scores = solve::solve(&game, None);
+                for (score, possible_move) in &scores {
+                    let mut new_game = game.clone();
+                    new_game.play_move(*possible_move).unwrap();
+                    assert_eq!(*score, -new_game.endgame_search_with_empties(SIZE).unwrap());
+                }
+
+                assert_eq!(scores, solve::solve(&game, Some(SIZE - game.total_moves())));
+            }
+        }
+    }
+
+    #[test]
+    fn test_negamax_with_table() {
+        let mut rng = fastrand::Rng::with_seed(252);
+        let mut shared = TranspositionTable::new();
+
+        for _ in 0..5 {
+            for game in random_game(&mut rng).into_iter().filter(|game| SIZE - game.total_moves() <= 7) {
+                let expected = full_negamax(&game);
+
+                let mut tt = TranspositionTable::new();
+                assert_eq!(negamax_with_table(&game, -(SIZE as isize), SIZE as isize, &mut tt).unwrap(), expected);
+                assert_eq!(negamax_with_table(&game, -(SIZE as isize), SIZE as isize, &mut tt).unwrap(), expected);
+                assert_eq!(negamax_with_table(&game, -(SIZE as isize), SIZE as isize, &mut shared).unwrap(), expected);
+
+                for (alpha, beta) in [(-1, 1), (expected - 1, expected + 1), (0, 1)] {
+                    let mut tt = TranspositionTable::new();
+                    let score = negamax_with_table(&game, alpha, beta, &mut tt).unwrap();
+
+                    if alpha < expected && expected < beta {
+                        assert_eq!(score, expected);
+                    } else if score <= alpha {
+                        assert!(expected <= score);
+                    } else {
+                        assert!(expected >= score);
+                    }
+                }
+            }
+        }
+
+        assert!(!shared.is_empty());
+    }
+
+    #[test]
+    fn test_order_moves() {
+        let game = Game::from_string("-OX-----\n\
+        --------\n\
+        --O-----\n\
+        ---X----\n\
+        XOO-----\n\
+        --------\n\
+        -XO-----\n\
+        -----XO-", Player::One, false).unwrap();
+
+        // b2 is an X-square, d5 flips two discs and d7 one
+        assert_eq!(order_moves(&game, &[9, 51, 0, 35, 63]), vec![0, 63, 35, 51, 9]);
+
+        let mut rng = fastrand::Rng::with_seed(254);
+
+        for game in random_game(&mut rng) {
+            let mut moves = game.move_indices();
+            let mut ordered = order_moves(&game, &moves);
+
+            moves.sort_unstable();
+            ordered.sort_unstable();
+
+            assert_eq!(moves, ordered);
+        }
+    }
+
+    #[test]
+    fn test_negamax_depth_consistency() {
+        let window = (-(SIZE as isize), SIZE as isize);
+        let mut rng = fastrand::Rng::with_seed(255);
+
+        for game in random_game(&mut rng).iter().step_by(3) {
+            for depth in 1..=2 {
+                let scores = solve::solve(game, Some(depth));
+                assert_eq!(scores, solve::solve_at_depth(game, depth).into_iter().map(|(score, idx)| (score, Move::from_index(idx))).collect::<Vec<_>>());
+
+                let score = negamax_depth(game, depth, window.0, window.1, &disc_difference).unwrap();
+
+                if game.terminal_score().is_none() && !scores.is_empty() {
+                    // one more ply of search is the best of the moves searched one ply less
+                    let best = game.move_indices().into_iter().map(|possible_move| {
+                        let mut new_game = game.clone();
+                        new_game.play_idx(possible_move).unwrap();
+                        -negamax_depth(&new_game, depth - 1, window.0, window.1, &disc_difference).unwrap()
+                    }).max().unwrap();
+
+                    assert_eq!(score, best);
+                    assert_eq!(scores.iter().map(|(score, _)| *score).max(), Some(negamax_depth(game, depth + 1, window.0, window.1, &disc_difference).unwrap()));
+                }
+            }
+
+            if game.terminal_score().is_none() {
+                assert_eq!(negamax_depth(game, 0, window.0, window.1, &|_: &Game| 7).unwrap(), 7);
+            }
+        }
+    }
+
+    #[test]
+    fn test_iterative_deepening_solve() {
+        let mut rng = fastrand::Rng::with_seed(256);
+
+        for game in random_game(&mut rng).iter().step_by(6) {
+            for depth in 1..=3 {
+                assert_eq!(iterative_deepening_solve(game, depth).unwrap(), solve::solve_at_depth(game, depth));
+            }
+        }
+
+        assert!(iterative_deepening_solve(&Game::new(), 0).is_err());
+    }
+
+    #[test]
+    fn test_solve_with_timeout() {
+        let mut rng = fastrand::Rng::with_seed(257);
+
+        for game in random_game(&mut rng).iter().step_by(8) {
+            let scores = solve_with_timeout(game, Duration::from_millis(20));
+
+            // whatever depth was reached, the scores are exactly those of that depth
+            assert!((0..=SIZE - game.total_moves()).any(|depth| solve::solve_at_depth(game, depth) == scores));
+        }
+
+        assert_eq!(solve_with_timeout(&Game::new(), Duration::ZERO), solve::solve_at_depth(&Game::new(), 0));
+
+        let endgame = random_game(&mut rng).into_iter().find(|game| SIZE - game.total_moves() == 6).unwrap();
+        assert_eq!(solve_with_timeout(&endgame, Duration::from_secs(60)), solve::solve_at_depth(&endgame, 6));
+    }
+}
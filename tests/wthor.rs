@@ -0,0 +1,93 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use reversi_solver::{Game, board::{Player, WIDTH}, record::GameRecord, wthor::{self, WThorGame}};
+
+    use crate::common::random_record;
+
+    fn header(games: u32, year: u16) -> Vec<u8> {
+        let mut header = vec![20, 24, 1, 2];
+        header.extend(games.to_le_bytes());
+        header.extend([0, 0]);
+        header.extend(year.to_le_bytes());
+        header.extend([8, 0, 22, 0]);
+        header
+    }
+
+    fn game_bytes(tournament: u16, black: u16, white: u16, scores: [u8; 2], moves: &[u8]) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        bytes.extend(tournament.to_le_bytes());
+        bytes.extend(black.to_le_bytes());
+        bytes.extend(white.to_le_bytes());
+        bytes.extend(scores);
+        bytes.extend(moves);
+        bytes.resize(68, 0);
+        bytes
+    }
+
+    /// A record's moves as WThor squares, mirrored back to the standard board and without passes.
+    fn to_squares(record: &GameRecord) -> Vec<u8> {
+        record.moves.iter().flatten().map(|idx| (10 * (idx / WIDTH + 1) + WIDTH - idx % WIDTH) as u8).collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        // the Tiger opening, f5 d6 c3 d3 c4, and an empty game
+        let tiger = [56, 64, 33, 34, 43];
+
+        let mut bytes = header(2, 2001);
+        bytes.extend(game_bytes(7, 300, 1200, [36, 33], &tiger));
+        bytes.extend(game_bytes(8, 5, 6, [0, 0], &[]));
+
+        let games = wthor::parse(&bytes).unwrap();
+
+        assert_eq!(games, vec![
+            WThorGame { year: 2001, tournament: 7, black_player: 300, white_player: 1200, black_score: 36, theoretical_score: 33, moves: tiger.to_vec() },
+            WThorGame { year: 2001, tournament: 8, black_player: 5, white_player: 6, black_score: 0, theoretical_score: 0, moves: Vec::new() },
+        ]);
+
+        // mirrored onto this board: c5 e6 f3 e3 f4
+        let positions = games[0].replay().unwrap();
+        assert_eq!(positions.len(), 6);
+        assert_eq!(positions[5], Game::from_moves(&[Some(34), Some(44), Some(21), Some(20), Some(29)]).unwrap());
+        assert_eq!(games[1].replay().unwrap(), vec![Game::new()]);
+
+        assert!(wthor::parse(&header(0, 2001)).unwrap().is_empty());
+        assert!(wthor::parse(&bytes[..15]).is_err());
+        assert!(wthor::parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(wthor::parse(&[header(3, 2001), bytes[16..].to_vec()].concat()).is_err());
+
+        let mut large = bytes.clone();
+        large[12] = 10;
+        assert!(wthor::parse(&large).is_err());
+    }
+
+    #[test]
+    fn test_replay() {
+        let mut rng = fastrand::Rng::with_seed(273);
+
+        let records = (0..10).map(|_| random_record(&mut rng)).collect::<Vec<_>>();
+
+        let mut bytes = header(records.len() as u32, 2024);
+        for record in &records {
+            let last = record.replay().unwrap().pop().unwrap();
+            bytes.extend(game_bytes(0, 1, 2, [last.count_discs_for(Player::One) as u8, 0], &to_squares(record)));
+        }
+
+        for (game, record) in wthor::parse(&bytes).unwrap().into_iter().zip(records) {
+            // the passes WThor leaves out are put back
+            assert_eq!(game.to_record().unwrap(), record);
+            assert_eq!(game.replay().unwrap(), record.replay().unwrap());
+
+            let last = game.replay().unwrap().pop().unwrap();
+            assert!(last.is_game_over());
+            assert_eq!(last.count_discs_for(Player::One), game.black_score as usize);
+        }
+
+        let invalid = |moves: &[u8]| WThorGame { year: 2024, tournament: 0, black_player: 0, white_player: 0, black_score: 0, theoretical_score: 0, moves: moves.to_vec() };
+        assert!(invalid(&[56, 9]).replay().is_err());
+        assert!(invalid(&[56, 90]).replay().is_err());
+        assert!(invalid(&[11]).replay().is_err());
+    }
+}
@@ -0,0 +1,98 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use reversi_solver::{board::{Board, Cell, Player}, zobrist::{ZobristHasher, ZOBRIST_PLAYER_TWO}, Game};
+
+    use crate::common::{random_game, random_record};
+
+    fn to_board(game: &Game) -> Board {
+        let mut board = Board::new();
+        for (idx, cell) in game.iter().enumerate() {
+            board.set_cell_idx(idx, cell);
+        }
+
+        board
+    }
+
+    #[test]
+    fn test_update_cell_and_hash() {
+        let zobrist = ZobristHasher::new(247);
+        let mut rng = fastrand::Rng::with_seed(247);
+
+        for game in random_game(&mut rng).iter().step_by(5) {
+            let mut game = game.clone();
+            let mut hash = zobrist.hash(&to_board(&game));
+
+            for _ in 0..20 {
+                let cell = match rng.u8(..3) {
+                    0 => Cell::Empty,
+                    1 => Cell::Player(Player::One),
+                    _ => Cell::Player(Player::Two),
+                };
+
+                game.update_cell_and_hash(rng.usize(..64), cell, &mut hash, &zobrist);
+
+                assert_eq!(hash, zobrist.hash(&to_board(&game)));
+            }
+        }
+
+        assert_eq!(ZobristHasher::new(1).hash(&Board::new()), ZobristHasher::new(1).hash(&Board::new()));
+        assert_ne!(zobrist.hash(&Board::new()), zobrist.hash(&to_board(&Game::new())));
+    }
+
+    #[test]
+    fn test_incremental_hash() {
+        let mut rng = fastrand::Rng::with_seed(253);
+
+        for _ in 0..20 {
+            for game in random_game(&mut rng) {
+                assert_eq!(game.hash(), game.compute_hash());
+
+                let side = if game.current_player() == Player::Two { *ZOBRIST_PLAYER_TWO } else { 0 };
+                assert_eq!(game.hash() ^ side, to_board(&game).compute_hash());
+            }
+        }
+
+        for _ in 0..20 {
+            let record = random_record(&mut rng);
+            let mut game = Game::new();
+            let mut hashes = vec![game.hash()];
+
+            for &turn in &record.moves {
+                game.play_turn(turn).unwrap();
+                assert_eq!(game.hash(), game.compute_hash());
+                hashes.push(game.hash());
+            }
+
+            while let Some(hash) = hashes.pop() {
+                assert_eq!(game.hash(), hash);
+                assert_eq!(game.hash(), game.compute_hash());
+
+                if !hashes.is_empty() {
+                    game.undo().unwrap();
+                }
+            }
+        }
+
+        let start = Game::new();
+        assert_eq!(start.hash(), start.compute_hash());
+        assert_ne!(start.hash(), Board::new().compute_hash());
+
+        let one_to_move = Game::from_fen("8/8/8/3XO3/3OX3/8/8/8 X").unwrap();
+        let two_to_move = Game::from_fen("8/8/8/3XO3/3OX3/8/8/8 O").unwrap();
+        assert_eq!(one_to_move.hash(), one_to_move.compute_hash());
+        assert_eq!(two_to_move.hash(), two_to_move.compute_hash());
+        assert_ne!(one_to_move.hash(), two_to_move.hash());
+        assert_eq!(one_to_move.hash() ^ *ZOBRIST_PLAYER_TWO, two_to_move.hash());
+
+        for game in random_game(&mut rng)[20].make_symmetric_positions() {
+            assert_eq!(game.hash(), game.compute_hash());
+        }
+
+        let mut edited = Game::new();
+        let mut hash = 0;
+        edited.update_cell_and_hash(0, Cell::Player(Player::One), &mut hash, &ZobristHasher::new(1));
+        assert_eq!(edited.hash(), edited.compute_hash());
+    }
+}